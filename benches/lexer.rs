@@ -0,0 +1,69 @@
+//lexing throughput on a generated multi-megabyte source
+//run with: cargo bench --bench lexer
+
+#![allow(dead_code)]
+
+#[path = "../src/lexer.rs"]
+mod lexer;
+#[path = "../src/definitions.rs"]
+mod definitions;
+#[path = "../src/parser.rs"]
+mod parser;
+#[path = "../src/statement.rs"]
+mod statement;
+#[path = "../src/expression.rs"]
+mod expression;
+
+use lexer::Lexer;
+use std::time::Instant;
+
+const TARGET_SIZE: usize = 8 * 1024 * 1024;
+const RUNS: u32 = 5;
+
+fn generate_source() -> String {
+    let chunk = r#"/* generated block */
+struct Point { x: u16, y: u16 }
+enum Color { Red, Green, Blue }
+
+fn letter_format(iffy: u16, letter: @u8) -> u16 {
+    let format: u16 = 0xFF00;
+    let mask: u8 = 0b1010_0101;
+    let offset: u16 = iffy + 255u16 << 2u16;
+    while format >= offset && mask != 0 {
+        letter[offset] = 'A';
+        break
+    }
+    return format // trailing comment
+}
+
+"#;
+    let mut text = String::with_capacity(TARGET_SIZE + chunk.len());
+    while text.len() < TARGET_SIZE {
+        text.push_str(chunk);
+    }
+    text
+}
+
+fn main() {
+    let text = generate_source();
+    let mb = text.len() as f64 / (1024.0 * 1024.0);
+
+    let mut best = f64::MAX;
+    let mut tokens = 0;
+
+    for _ in 0..RUNS {
+        let mut l = Lexer::new(text.clone(), 0);
+
+        let start = Instant::now();
+        let t = l.lex();
+        let elapsed = start.elapsed().as_secs_f64();
+
+        assert!(l.errors.is_empty(), "generated source should lex");
+
+        tokens = t.len();
+        best = best.min(elapsed);
+    }
+
+    println!("lexed {:.1} MiB ({} tokens) in {:.3} ms", mb, tokens, best * 1000.0);
+    println!("throughput: {:.1} MiB/s, {:.1} Mtokens/s", mb / best, tokens as f64 / best / 1e6);
+}
@@ -1,10 +1,613 @@
-/*use super::definitions::*;
-use super:: statement::*;
+use super::definitions::*;
+use super::statement::*;
 use super::expression::*;
+use super::syntax::{CheckedInfo, DataItem};
 
 
-struct CodeGenerator {
+pub struct CodeGenerator {
     program: Vec<Statement>,
-    defined: Vec<VarType>,
+    rodata: ReadOnlyData,
+    text: String,
+    labels: usize,
+    loops: Vec<LoopLabels>,
+    info: CheckedInfo,
+    symbols: Vec<String>, //.extern and .global directives
+    nested_fns: Vec<Statement>, //functions declared inside the body being generated
     output: String,
-}*/
\ No newline at end of file
+}
+
+//where break and continue jump to inside the innermost loop
+struct LoopLabels {
+    label: Option<String>,
+    continue_label: String,
+    break_label: String,
+}
+
+impl CodeGenerator {
+    pub fn new(program: Vec<Statement>, info: CheckedInfo) -> Self {
+        CodeGenerator {
+            program,
+            rodata: ReadOnlyData { entries: vec![], tables: vec![] },
+            text: String::new(),
+            labels: 0,
+            loops: vec![],
+            info,
+            symbols: vec![],
+            nested_fns: vec![],
+            output: String::new(),
+        }
+    }
+
+    pub fn generate(&mut self) -> String {
+        let program = self.program.clone();
+
+        for stmt in program.iter().filter(|s| matches!(s, Statement::FnDeclr(_))) {
+            self.gen_statement(stmt);
+        }
+
+        for symbol in &self.symbols {
+            self.output.push_str(&format!("{}\n", symbol));
+        }
+
+        self.output.push_str(".section .text\n");
+        self.output.push_str(&self.text);
+        self.gen_statics(&program);
+        self.rodata.emit(&mut self.output);
+        self.output.clone()
+    }
+
+    //statics with a value go in .data, the rest are zeroed in .bss
+    fn gen_statics(&mut self, program: &[Statement]) {
+        let mut data = String::new();
+        let mut bss = String::new();
+
+        for stmt in program {
+            if let Statement::StaticDeclr(declr, _) = stmt {
+                let size = self.info.static_sizes[&declr.name.span];
+
+                match self.info.static_data.get(&declr.name.span).cloned() {
+                    Some(items) => {
+                        data.push_str(&format!("{}:\n", declr.name.data()));
+                        for item in items {
+                            for directive in self.data_directives(item) {
+                                data.push_str(&format!("    {}\n", directive));
+                            }
+                        }
+                    }
+                    None => bss.push_str(&format!("{}:\n    .zero {}\n", declr.name.data(), size))
+                }
+            }
+        }
+
+        if !data.is_empty() {
+            self.output.push_str(".section .data\n");
+            self.output.push_str(&data);
+        }
+
+        if !bss.is_empty() {
+            self.output.push_str(".section .bss\n");
+            self.output.push_str(&bss);
+        }
+    }
+
+    //little endian, values wider than a word are split into words
+    fn data_directives(&mut self, item: DataItem) -> Vec<String> {
+        match item {
+            DataItem::Value(1, v) => vec![format!(".byte {}", v & 0xFF)],
+            DataItem::Value(size, v) => (0..size / 2).map(|n| format!(".word {}", (v >> (16 * n)) & 0xFFFF)).collect(),
+            DataItem::Label(label) => vec![format!(".word {}", label)],
+            DataItem::Str(bytes) => vec![format!(".word {}", self.rodata.intern(&bytes))]
+        }
+    }
+
+    fn emit(&mut self, instr: &str) {
+        self.text.push_str(&format!("    {}\n", instr));
+    }
+
+    fn emit_label(&mut self, label: &str) {
+        self.text.push_str(&format!("{}:\n", label));
+    }
+
+    fn new_label(&mut self, name: &str) -> String {
+        self.labels += 1;
+        format!("__{}_{}", name, self.labels)
+    }
+
+    fn gen_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::FnDeclr(declr) if declr.linkage == Linkage::Extern => {
+                self.symbols.push(format!(".extern {}", declr.name.data()));
+            }
+            Statement::FnDeclr(declr) => {
+                if declr.linkage == Linkage::Export {
+                    self.symbols.push(format!(".global {}", declr.name.data()));
+                }
+
+                self.emit_label(&declr.name.data());
+                self.gen_statement(&declr.body);
+                self.emit("ret");
+
+                //nested functions go after their parent so it doesn't run into them
+                for nested in std::mem::take(&mut self.nested_fns) {
+                    self.gen_statement(&nested);
+                }
+            }
+            Statement::VarDeclr(declr) => {
+                if let Some(value) = &declr.value {
+                    let value = self.gen_expr(value);
+                    self.emit(&format!("mov {}, {}", declr.name.data(), value));
+                }
+            }
+            Statement::LoopStmt(body, label) => {
+                let start = self.new_label("loop");
+                let end = self.new_label("end");
+
+                self.emit_label(&start);
+                self.gen_loop_body(body, label, &start, &end);
+                self.emit(&format!("jmp {}", start));
+                self.emit_label(&end);
+            }
+            Statement::WhileStmt(stmt, label) => {
+                let start = self.new_label("while");
+                let end = self.new_label("end");
+
+                self.emit_label(&start);
+                let cond = self.gen_expr(&stmt.cond);
+                self.emit(&format!("jz {}, {}", cond, end));
+                self.gen_loop_body(&stmt.true_branch, label, &start, &end);
+                self.emit(&format!("jmp {}", start));
+                self.emit_label(&end);
+            }
+            Statement::ForStmt(stmt) => self.gen_for(stmt),
+            Statement::MatchStmt(stmt) => self.gen_match(stmt),
+            Statement::AsmStmt(stmt) => self.gen_asm(stmt),
+            Statement::IfStmt(stmt) => {
+                let else_label = self.new_label("else");
+                let end = self.new_label("endif");
+
+                let cond = self.gen_expr(&stmt.cond);
+                self.emit(&format!("jz {}, {}", cond, else_label));
+                self.gen_statement(&stmt.true_branch);
+                self.emit(&format!("jmp {}", end));
+                self.emit_label(&else_label);
+                if let Some(f) = &stmt.false_branch {
+                    self.gen_statement(f);
+                }
+                self.emit_label(&end);
+            }
+            Statement::BreakStmt(_, label) => {
+                let target = self.find_loop(label).break_label.clone();
+                self.emit(&format!("jmp {}", target));
+            }
+            Statement::ContinueStmt(_, label) => {
+                let target = self.find_loop(label).continue_label.clone();
+                self.emit(&format!("jmp {}", target));
+            }
+            Statement::ReturnStmt(_, e) => {
+                let value = self.gen_expr(e);
+                self.emit(&format!("ret {}", value));
+            }
+            Statement::ExprStmt(e) => {
+                let value = self.gen_expr(e);
+                self.emit(&format!("eval {}", value));
+            }
+            Statement::Block(body) => {
+                for s in body {
+                    match s {
+                        Statement::FnDeclr(_) => self.nested_fns.push(s.clone()),
+                        _ => self.gen_statement(s)
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn gen_loop_body(&mut self, body: &Statement, label: &Option<Token>, continue_label: &str, break_label: &str) {
+        self.loops.push(LoopLabels {
+            label: label.as_ref().map(|l| l.data()),
+            continue_label: continue_label.to_string(),
+            break_label: break_label.to_string() });
+        self.gen_statement(body);
+        self.loops.pop();
+    }
+
+    fn find_loop(&self, label: &Option<Token>) -> &LoopLabels {
+        let found = match label {
+            Some(l) => self.loops.iter().rev().find(|lp| lp.label == Some(l.data())),
+            None => self.loops.last()
+        };
+
+        found.expect("jumps outside loops should have been checked")
+    }
+
+    //the end and step are evaluated once, the latch steps and then leaves
+    //if the variable wrapped around or passed the end, nothing is subtracted
+    fn gen_for(&mut self, stmt: &ForStmt) {
+        let start = self.new_label("for");
+        let next = self.new_label("next");
+        let end = self.new_label("end");
+
+        let var = stmt.var.data();
+        let end_var = format!("{}_end", start);
+        let step_var = format!("{}_step", start);
+        let prev_var = format!("{}_prev", start);
+
+        let start_value = self.gen_expr(&stmt.start);
+        let end_value = self.gen_expr(&stmt.end);
+        let step_value = match &stmt.step {
+            Some(step) => self.gen_expr(step),
+            None => "1".to_string()
+        };
+
+        let enter = if stmt.inclusive {"<="} else {"<"};
+
+        self.emit(&format!("mov {}, {}", var, start_value));
+        self.emit(&format!("mov {}, {}", end_var, end_value));
+        self.emit(&format!("mov {}, {}", step_var, step_value));
+        self.emit(&format!("jz ({} {} {}), {}", var, enter, end_var, end));
+
+        self.emit_label(&start);
+        self.gen_loop_body(&stmt.body, &stmt.label, &next, &end);
+
+        self.emit_label(&next);
+        self.emit(&format!("mov {}, {}", prev_var, var));
+        self.emit(&format!("mov {}, ({} + {})", var, var, step_var));
+        self.emit(&format!("jz ({} > {}), {}", var, prev_var, end));
+        self.emit(&format!("jz ({} {} {}), {}", var, enter, end_var, end));
+        self.emit(&format!("jmp {}", start));
+        self.emit_label(&end);
+    }
+
+    //inputs that aren't plain variables are evaluated into temporaries first,
+    //then each line is emitted as written with the operands filled in
+    fn gen_asm(&mut self, stmt: &AsmStmt) {
+        let mut bindings: Vec<(String, String)> = vec![];
+
+        for operand in &stmt.operands {
+            let value = self.gen_expr(&operand.value);
+
+            let is_name = value.chars().all(|c| c.is_alphanumeric() || c == '_');
+            if operand.kind == AsmOperandKind::In && !is_name {
+                let temp = self.new_label("asm_in");
+                self.emit(&format!("mov {}, {}", temp, value));
+                bindings.push((operand.name.data(), temp));
+            } else {
+                bindings.push((operand.name.data(), value));
+            }
+        }
+
+        //variables live in memory and nothing is kept in registers across statements,
+        //so the clobber list has nothing to save and is only kept for whoever reads the output
+        if !stmt.clobbers.is_empty() {
+            let clobbers: Vec<String> = stmt.clobbers.iter().map(|c| c.data()).collect();
+            self.emit(&format!("; clobbers {}", clobbers.join(", ")));
+        }
+
+        for line in &stmt.lines {
+            let pieces = asm_template(&line.data()).expect("asm templates should have been checked");
+
+            let mut out = String::new();
+            for piece in pieces {
+                match piece {
+                    AsmPiece::Text(t) => out.push_str(&t),
+                    AsmPiece::Operand(name) => {
+                        let binding = bindings.iter().find(|b| b.0 == name).expect("asm operands should have been checked");
+                        out.push_str(&binding.1);
+                    }
+                }
+            }
+
+            self.emit(&out);
+        }
+    }
+
+    //dense matches jump through a table indexed by the discriminant,
+    //sparse ones compare against each discriminant in turn
+    fn gen_match(&mut self, stmt: &MatchStmt) {
+        let value_var = self.new_label("match");
+        let end = self.new_label("end");
+        let arm_labels: Vec<String> = stmt.arms.iter().map(|_| self.new_label("arm")).collect();
+
+        let value = self.gen_expr(&stmt.value);
+        self.emit(&format!("mov {}, {}", value_var, value));
+
+        //without a wildcard arm the checker made sure every variant has an arm
+        let default = match stmt.arms.iter().position(|a| a.patterns.iter().any(|p| matches!(p, MatchPattern::Wildcard(_)))) {
+            Some(n) => arm_labels[n].clone(),
+            None => end.clone()
+        };
+
+        let variants = match self.info.match_discriminants.iter().find(|m| m.0 == stmt.keyword.span) {
+            Some(m) => m.1.clone(),
+            None => vec![]
+        };
+
+        //discriminant and where it jumps to
+        let targets: Vec<(i128, String)> = variants.iter().map(|(name, d)| {
+            let arm = stmt.arms.iter().position(|a| a.patterns.iter().any(|p| match p {
+                MatchPattern::Variant(_, v) => v.data() == *name,
+                MatchPattern::Wildcard(_) => false
+            }));
+
+            (*d, arm.map_or(default.clone(), |n| arm_labels[n].clone()))
+        }).collect();
+
+        let min = targets.iter().map(|t| t.0).min();
+        let max = targets.iter().map(|t| t.0).max();
+
+        match (min, max) {
+            (Some(min), Some(max)) if max - min < 2 * targets.len() as i128 => {
+                let mut table = vec![default.clone(); (max - min + 1) as usize];
+                for (d, label) in targets {
+                    table[(d - min) as usize] = label;
+                }
+
+                //a value cast from an integer at runtime may be outside the table
+                self.emit(&format!("jz ({} >= {}), {}", value_var, min, default));
+                self.emit(&format!("jz ({} <= {}), {}", value_var, max, default));
+
+                let table_label = self.rodata.jump_table(table);
+                let index = match min {
+                    0 => value_var.clone(),
+                    m if m < 0 => format!("({} + {})", value_var, -m),
+                    m => format!("({} - {})", value_var, m)
+                };
+                self.emit(&format!("jtab {}, {}", index, table_label));
+            }
+            _ => {
+                for (d, label) in targets.iter().filter(|t| t.1 != default) {
+                    self.emit(&format!("jz ({} != {}), {}", value_var, d, label));
+                }
+                self.emit(&format!("jmp {}", default));
+            }
+        }
+
+        for (arm, label) in stmt.arms.iter().zip(arm_labels) {
+            self.emit_label(&label);
+            self.gen_statement(&arm.body);
+            self.emit(&format!("jmp {}", end));
+        }
+        self.emit_label(&end);
+    }
+
+    //constants are replaced by the value the checker worked out
+    fn const_value(&self, t: &Token) -> String {
+        self.info.const_values.get(&t.span).expect("constants should have been evaluated").to_string()
+    }
+
+    //expressions are emitted whole for now, string literals become their rodata label
+    fn gen_expr(&mut self, expr: &Expr) -> String {
+        match expr {
+            Expr::Base => String::new(),
+            Expr::Assign(e) | Expr::LogicOr(e) | Expr::LogicAnd(e) |
+            Expr::Equality(e) | Expr::Comparison(e) |
+            Expr::BitOr(e) | Expr::BitXor(e) | Expr::BitAnd(e) |
+            Expr::Shift(e) | Expr::Term(e) | Expr::Factor(e) => {
+                let left = self.gen_expr(&e.left);
+                let right = self.gen_expr(&e.right);
+                format!("({} {} {})", left, e.operator.data(), right)
+            }
+            Expr::Unary(e) if self.info.const_values.contains_key(&e.operator.span) => self.const_value(&e.operator),
+            Expr::Unary(e) => format!("({}{})", e.operator.data(), self.gen_expr(&e.right)),
+            Expr::Cast(c) => format!("({} as {})", self.gen_expr(&c.value), c.to_type),
+            Expr::Ref(r) => format!("{}{}", r.operator.data(), self.gen_expr(&r.right)),
+            Expr::FnCall(call) => {
+                let args: Vec<String> = call.args.iter().map(|a| self.gen_expr(a)).collect();
+                let callee = self.gen_expr(&call.callee);
+
+                //through a function pointer the callee is read first
+                if self.info.indirect_calls.contains(&call.paren.span) {
+                    format!("(*{})({})", callee, args.join(", "))
+                } else {
+                    format!("{}({})", callee, args.join(", "))
+                }
+            }
+            Expr::Primary(p) => match &**p {
+                PrimaryExpr::Grouping(e) => self.gen_expr(e),
+                PrimaryExpr::Literal(Token {ttype: TokenType::Str(bytes), ..}) => self.rodata.intern(bytes),
+                PrimaryExpr::Literal(Token {ttype: TokenType::Char(c), ..}) => c.to_string(),
+                PrimaryExpr::Literal(l) if self.info.const_values.contains_key(&l.span) => self.const_value(l),
+                PrimaryExpr::Literal(l) => l.data(),
+                PrimaryExpr::Id(id) if self.info.const_values.contains_key(&id.span) => self.const_value(id),
+                PrimaryExpr::Id(id) => id.data(),
+                //through a pointer the struct is loaded first, like an indirect call
+                PrimaryExpr::StructField(s, field) if self.info.pointer_fields.contains(&field.span) => {
+                    format!("(*{}).{}", self.gen_expr(s), field.data())
+                }
+                PrimaryExpr::StructField(s, field) => format!("{}.{}", self.gen_expr(s), field.data()),
+                PrimaryExpr::EnumVariant(_, variant) => self.const_value(variant),
+                PrimaryExpr::ArrayAccess(array, index) => {
+                    let array = self.gen_expr(array);
+                    format!("{}[{}]", array, self.gen_expr(index))
+                }
+                PrimaryExpr::StructLit(name, fields) => {
+                    let fields: Vec<String> = fields.iter().map(|(n, v)| format!("{}: {}", n.data(), self.gen_expr(v))).collect();
+                    format!("{} {{{}}}", name.data(), fields.join(", "))
+                }
+                PrimaryExpr::ArrayLit(elements) => {
+                    let elements: Vec<String> = elements.iter().map(|e| self.gen_expr(e)).collect();
+                    format!("[{}]", elements.join(", "))
+                }
+                PrimaryExpr::ArrayRepeat(value, count) => {
+                    let value = self.gen_expr(value);
+                    format!("[{}; {}]", value, self.gen_expr(count))
+                }
+                PrimaryExpr::SizeOf(keyword, _) | PrimaryExpr::AlignOf(keyword, _) |
+                PrimaryExpr::OffsetOf(keyword, _, _) => self.const_value(keyword),
+            },
+        }
+    }
+}
+
+
+//string literals live in a read-only section, identical literals share one label
+pub struct ReadOnlyData {
+    entries: Vec<Vec<u8>>,
+    tables: Vec<Vec<String>>, //jump tables, one label per entry
+}
+
+impl ReadOnlyData {
+    pub fn intern(&mut self, bytes: &[u8]) -> String {
+        let n = match self.entries.iter().position(|e| e == bytes) {
+            Some(n) => n,
+            None => {
+                self.entries.push(bytes.to_vec());
+                self.entries.len() - 1
+            }
+        };
+
+        Self::label(n)
+    }
+
+    fn label(n: usize) -> String {
+        format!("__str_{}", n)
+    }
+
+    pub fn jump_table(&mut self, labels: Vec<String>) -> String {
+        self.tables.push(labels);
+        Self::table_label(self.tables.len() - 1)
+    }
+
+    fn table_label(n: usize) -> String {
+        format!("__table_{}", n)
+    }
+
+    pub fn emit(&self, out: &mut String) {
+        if self.entries.is_empty() && self.tables.is_empty() {
+            return
+        }
+
+        out.push_str(".section .rodata\n");
+        for (n, bytes) in self.entries.iter().enumerate() {
+            out.push_str(&format!("{}:    ; \"{}\"\n", Self::label(n), bytes.escape_ascii()));
+
+            if bytes.is_empty() {
+                continue;
+            }
+
+            let bytes: Vec<String> = bytes.iter().map(|b| b.to_string()).collect();
+            out.push_str(&format!("    .byte {}\n", bytes.join(", ")));
+        }
+
+        for (n, labels) in self.tables.iter().enumerate() {
+            out.push_str(&format!("{}:\n", Self::table_label(n)));
+            out.push_str(&format!("    .word {}\n", labels.join(", ")));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::syntax::check_ast_syntax;
+
+    fn generate(text: &str) -> String {
+        let tokens = Lexer::new(text.to_string(), 0).lex();
+        let mut p = Parser::new(tokens);
+        let ast = p.parse();
+
+        assert!(p.errors.is_empty(), "{} did not parse", text);
+        let info = check_ast_syntax(ast.clone()).expect("should have checked");
+        CodeGenerator::new(ast, info).generate()
+    }
+
+    #[test]
+    fn enum_values() {
+        let out = generate("enum E: u8 {A = 1, B = 5}\nstatic s: E = E::B;\nfn main() -> void {\n let x: E = E::A;\n}");
+
+        assert!(out.contains("mov x, 1\n"), "{}", out);
+        assert!(!out.contains("E::"), "{}", out);
+    }
+
+    #[test]
+    fn match_table_bounds() {
+        let out = generate("enum E {A, B, C}\nfn f(n: u8) -> void {\n match n as E {\n E::A => {}\n E::B => {}\n _ => {}\n }\n}");
+
+        //out of range values go to the wildcard arm before the table is indexed
+        let jtab = out.find("jtab").expect("dense match should use a table");
+        let low = out.find("jz (__match_1 >= 0), __arm_5").expect(&out);
+        let high = out.find("jz (__match_1 <= 2), __arm_5").expect(&out);
+        assert!(low < jtab && high < jtab, "{}", out);
+    }
+
+    #[test]
+    fn static_data() {
+        let out = generate("struct P {x: u8, y: u16}\nstatic p: P = P {y: 0x1234, x: 7};\nstatic a: [u8; 3] = [1; 3];\nstatic big: u32 = 0x12345678;\nstatic s: [u8; 2] = \"hi\";\nstatic mut v: u8;\nstatic pv: @u8 = &v;\nfn main() -> void {\n v = 3;\n}\nstatic main_fn: fn() -> void = main;");
+
+        //fields in declaration order, words little endian
+        assert!(out.contains("p:\n    .byte 7\n    .word 4660\n"), "{}", out);
+        assert!(out.contains("a:\n    .byte 1\n    .byte 1\n    .byte 1\n"), "{}", out);
+        assert!(out.contains("big:\n    .word 22136\n    .word 4660\n"), "{}", out);
+        assert!(out.contains("s:\n    .byte 104\n    .byte 105\n"), "{}", out);
+        assert!(out.contains("pv:\n    .word v\n"), "{}", out);
+        assert!(out.contains("main_fn:\n    .word main\n"), "{}", out);
+
+        //statics without a value are zeroed in .bss
+        let bss = out.find(".section .bss").expect(&out);
+        assert!(out[bss..].contains("v:\n    .zero 1\n"), "{}", out);
+        assert!(!out.contains(".init"), "{}", out);
+    }
+
+    #[test]
+    fn for_ranges() {
+        let exclusive = generate("fn main() -> void {\n for i: u8 in 0..10 {}\n}");
+        assert!(exclusive.contains("mov i, 0\n    mov __for_1_end, 10\n    mov __for_1_step, 1\n    jz (i < __for_1_end), __end_3\n"), "{}", exclusive);
+        assert!(exclusive.contains("    jz (i < __for_1_end), __end_3\n    jmp __for_1\n"), "{}", exclusive);
+
+        let stepped = generate("fn main() -> void {\n for i: u8 in 0..=20 step 5 {}\n}");
+        assert!(stepped.contains("mov __for_1_step, 5\n    jz (i <= __for_1_end), __end_3\n"), "{}", stepped);
+
+        //255 + 1 wraps to 0 in a u8, which is caught by comparing with the value before the step
+        let wrapping = generate("fn main() -> void {\n for i: u8 in 0..=255 {}\n}");
+        assert!(wrapping.contains("    mov __for_1_prev, i\n    mov i, (i + __for_1_step)\n    jz (i > __for_1_prev), __end_3\n    jz (i <= __for_1_end), __end_3\n"), "{}", wrapping);
+    }
+
+    #[test]
+    fn asm_operands() {
+        let out = generate("fn main() -> void {\n let p: u8 = 1;\n let s: u16 = 0;\n asm { \"out {port}, {value}\"; \"in {status}, {{0}}\"; in port = p; in value = p + 1; out status = s; clobber a; }\n}");
+
+        //names are used as they are, anything else goes through a temporary first
+        assert!(out.contains("    mov __asm_in_1, (p + 1)\n    ; clobbers a\n    out p, __asm_in_1\n    in s, {0}\n"), "{}", out);
+    }
+
+    #[test]
+    fn loop_labels() {
+        let out = generate("fn main() -> void {\n 'outer: loop {\n  while 1 == 1 {\n   continue 'outer;\n  }\n  break 'outer;\n }\n}");
+
+        //continue goes back to the top of the labeled loop, break to its end
+        assert!(out.contains("__loop_1:\n__while_3:\n    jz (1 == 1), __end_4\n    jmp __loop_1\n"), "{}", out);
+        assert!(out.contains("__end_4:\n    jmp __end_2\n"), "{}", out);
+    }
+
+    #[test]
+    fn sparse_match() {
+        let out = generate("enum E: u8 {A = 1, B = 100, C = 200}\nfn f(e: E) -> void {\n match e {\n E::A | E::C => {}\n E::B => {}\n }\n}");
+
+        //too spread out for a table, so each discriminant is compared in turn
+        assert!(!out.contains("jtab"), "{}", out);
+        assert!(out.contains("    jz (__match_1 != 1), __arm_3\n    jz (__match_1 != 100), __arm_4\n    jz (__match_1 != 200), __arm_3\n    jmp __end_2\n"), "{}", out);
+    }
+
+    #[test]
+    fn consts_are_folded() {
+        let out = generate("const N: u16 = 2 * 4 + (1 << 3);\nconst S: u16 = sizeof(u32) + N;\nstatic buf: [u8; N];\nfn main() -> void {\n let x: u16 = S;\n}");
+
+        assert!(out.contains("mov x, 20\n"), "{}", out);
+        assert!(out.contains("buf:\n    .zero 16\n"), "{}", out);
+        assert!(!out.contains("mov x, S"), "{}", out);
+    }
+
+    #[test]
+    fn linkage() {
+        let out = generate("extern fn putc(c: u8) -> void;\nexport fn tick(n: u16) -> u16 {\n putc(65);\n return n + 1\n}");
+
+        //an extern fn is only a symbol, the exported one keeps its name for assembly to call
+        assert!(out.contains(".extern putc\n"), "{}", out);
+        assert!(out.contains(".global tick\n"), "{}", out);
+        assert!(!out.contains("putc:"), "{}", out);
+        assert!(out.contains("tick:\n    eval putc(65)\n"), "{}", out);
+    }
+}
@@ -1,10 +1,27 @@
 use super::statement::*;
-use super::parser::Parser;
+use super::expression::*;
+use super::parser::{Parser, ParseError};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     pub ttype: TokenType,
-    pub pos: usize,
+    pub span: Span,
+}
+
+pub type FileId = usize;
+
+//byte range [start, end) of a token in one of the files of the SourceMap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub file: FileId,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub const fn new(file: FileId, start: usize, end: usize) -> Self {
+        Span { file, start, end }
+    }
 }
 
 impl Token {
@@ -13,6 +30,7 @@ impl Token {
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
     Key(String),
@@ -20,6 +38,11 @@ pub enum TokenType {
     Cond(String),
     Id(String),
     Lit(String),
+    Char(u8),
+    Str(Vec<u8>),
+    Label(String),    //'name, stored without the quote
+    Doc(String),      //outer doc comment, documents the next item
+    InnerDoc(String), //inner doc comment, documents the enclosing item
     ParenOpen,
     ParenClose,
     SquareOpen,
@@ -30,7 +53,11 @@ pub enum TokenType {
     Col,
     Comma,
     Period,
+    Range,     //..
+    RangeIncl, //..=
     Arrow,
+    FatArrow,
+    Error, //left by the lexer where it could not make sense of the text
     EOF,
 }
 
@@ -42,11 +69,30 @@ impl TokenType {
             TokenType::Cond(d) => d.clone(),
             TokenType::Id(d) => d.clone(),
             TokenType::Lit(d) => d.clone(),
+            TokenType::Str(d) => String::from_utf8_lossy(d).to_string(),
+            TokenType::Label(d) => d.clone(),
+            TokenType::Doc(d) => d.clone(),
+            TokenType::InnerDoc(d) => d.clone(),
             _ => panic!("Token didn't have data")
         }
     }
 }
 
+impl TokenType {
+    //how the token is named in error messages
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Id(d) if d.is_empty() => "Identifier".to_string(),
+            Self::Lit(d) if d.is_empty() => "Literal".to_string(),
+            Self::Label(d) if d.is_empty() => "Label".to_string(),
+            Self::Doc(_) | Self::InnerDoc(_) => "Doc Comment".to_string(),
+            Self::EOF => "End of File".to_string(),
+            Self::Error => "Invalid Token".to_string(),
+            _ => format!("`{}`", self)
+        }
+    }
+}
+
 impl std::fmt::Display for TokenType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -55,6 +101,11 @@ impl std::fmt::Display for TokenType {
             Self::Cond(d) => write!(f, "{}", d),
             Self::Id(d) => write!(f, "{}", d),
             Self::Lit(d) => write!(f, "{}", d),
+            Self::Char(c) => write!(f, "'{}'", c.escape_ascii()),
+            Self::Str(s) => write!(f, "\"{}\"", s.escape_ascii()),
+            Self::Label(l) => write!(f, "'{}", l),
+            Self::Doc(d) => write!(f, "///{}", d),
+            Self::InnerDoc(d) => write!(f, "//!{}", d),
             Self::ParenOpen => write!(f, "("),
             Self::ParenClose => write!(f, ")"),
             Self::SquareOpen => write!(f, "["),
@@ -64,9 +115,13 @@ impl std::fmt::Display for TokenType {
             Self::SemiCol => write!(f, ";"),
             Self::Col => write!(f, ":"),
             Self::Arrow => write!(f, "->"),
+            Self::FatArrow => write!(f, "=>"),
+            Self::Error => write!(f, "<error>"),
             Self::EOF => write!(f, "EOF"),
             Self::Comma => write!(f, ","),
             Self::Period => write!(f, "."),
+            Self::Range => write!(f, ".."),
+            Self::RangeIncl => write!(f, "..="),
         }
     }
 }
@@ -84,21 +139,23 @@ impl Statement {
 #[derive(Debug, Clone, PartialEq)]
 pub enum DeclrType {
     BasicType(Token),
-    Array(Box<DeclrType>, Token),
+    Array(Box<DeclrType>, Expr), //size is a constant expression
     Pointer(Box<DeclrType>),
+    Func(Token, Vec<DeclrType>, Box<DeclrType>), //(fn keyword, params, return type)
 }
 
 impl DeclrType {
     pub fn get_token(&self) -> Token {
         match self.clone() {
             Self::BasicType(t) => t,
-            Self::Array(t, s) => t.get_token(),
+            Self::Array(t, _) => t.get_token(),
             Self::Pointer(t) => t.get_token(),
+            Self::Func(keyword, _, _) => keyword,
         }
     }
 }
 
-pub fn parse_type(p: &mut Parser) -> Result<DeclrType, &'static str> {
+pub fn parse_type(p: &mut Parser) -> Result<DeclrType, ParseError> {
     let vtype;
     
     if matches!(p.peek(0).ttype, TokenType::Id(_)) {
@@ -111,19 +168,14 @@ pub fn parse_type(p: &mut Parser) -> Result<DeclrType, &'static str> {
         let array_type = parse_type(p)?;
 
         if p.peek(0).ttype != TokenType::SemiCol {
-            return Err("Expected Semicolon after Array Type")
+            return Err(p.error("Expected Semicolon after Array Type", vec![TokenType::SemiCol]))
         }
         p.advance();
 
-        if !matches!(p.peek(0).ttype, TokenType::Lit(_)) {
-            return Err("Expected Literal for Array Size")
-        }
-
-        let array_size = p.peek(0);
-        p.advance();
+        let array_size = new_expr("Base").parse(p)?;
         
         if p.peek(0).ttype != TokenType::SquareClose {
-            return Err("Expected Closing Square Bracket after Array Size")
+            return Err(p.error("Expected Closing Square Bracket after Array Size", vec![TokenType::SquareClose]))
         }
         p.advance();
 
@@ -134,8 +186,40 @@ pub fn parse_type(p: &mut Parser) -> Result<DeclrType, &'static str> {
         let points_to_type = parse_type(p)?;
         return Ok(DeclrType::Pointer(Box::new(points_to_type)))
 
+    } else if p.peek(0).ttype == TokenType::Key("fn".to_string()) {
+        let keyword = p.peek(0);
+        p.advance();
+
+        if p.peek(0).ttype != TokenType::ParenOpen {
+            return Err(p.error("Expected Parenthesis after fn in Function Type", vec![TokenType::ParenOpen]))
+        }
+        p.advance();
+
+        let mut params: Vec<DeclrType> = vec![];
+        while p.peek(0).ttype != TokenType::ParenClose {
+            params.push(parse_type(p)?);
+
+            if p.peek(0).ttype == TokenType::ParenClose {
+                break
+            }
+
+            if p.peek(0).ttype != TokenType::Comma {
+                return Err(p.error("Expected Parameter Types to be Seperated by Commas", vec![TokenType::Comma, TokenType::ParenClose]))
+            }
+            p.advance();
+        }
+        p.advance();
+
+        if p.peek(0).ttype != TokenType::Arrow {
+            return Err(p.error("Expected Arrow after Function Type Parameters", vec![TokenType::Arrow]).hint("function types are written as fn(params) -> type"))
+        }
+        p.advance();
+
+        let ret_type = parse_type(p)?;
+        vtype = DeclrType::Func(keyword, params, Box::new(ret_type))
+
     } else {
-        return Err("Cannot Parse Type")
+        return Err(p.error("Cannot Parse Type", vec![TokenType::Id(String::new()), TokenType::SquareOpen, TokenType::Key("@".to_string()), TokenType::Key("fn".to_string())]).hint("a type is a name, [type; size], @type or fn(params) -> type"))
     }
     
     
@@ -147,4 +231,4 @@ pub static RESERVED_IDS: [&str; 1] = [
     "void",
 ];
 
-pub const BLANK_TOKEN: Token = Token { ttype: TokenType::Arrow, pos: 0 };
\ No newline at end of file
+pub const BLANK_TOKEN: Token = Token { ttype: TokenType::Arrow, span: Span::new(0, 0, 0) };
\ No newline at end of file
@@ -1,452 +1,578 @@
 use crate::definitions::*;
 use crate::parser::*;
 
-
-#[derive(Clone, Debug)]
+/*
+Operator precedence, loosest binding first.
+Expressions are parsed by precedence climbing over the INFIX_OPS, PREFIX_OPS
+and POSTFIX_OPS tables below, adding an operator is one row in a table.
+Binary operators are left associative unless marked otherwise.
+
+    Level       Operators               Expr
+    ASSIGN      =                       Assign (right associative)
+    LOGIC_OR    ||                      LogicOr
+    LOGIC_AND   &&                      LogicAnd
+    EQUALITY    == !=                   Equality
+    COMP        < > <= >=               Comparison
+    BIT_OR      |                       BitOr
+    BIT_XOR     ^                       BitXor
+    BIT_AND     &                       BitAnd
+    SHIFT       << >>                   Shift
+    TERM        + -                     Term
+    FACTOR      * / %                   Factor
+    UNARY       - ! ~  (prefix)         Unary
+    CAST        as     (postfix)        Cast
+    REF         * &    (prefix)         Ref
+    POSTFIX     f(args) a[i] s.field    FnCall, ArrayAccess, StructField (chainable)
+    primary     literals, ids, (), enum variants, struct and array literals
+*/
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum Expr {
     Base,
     Assign(Box<BinaryExpr>),
+    LogicOr(Box<BinaryExpr>),
+    LogicAnd(Box<BinaryExpr>),
     Equality(Box<BinaryExpr>),
     Comparison(Box<BinaryExpr>),
-    Term(Box<BinaryExpr>),
+    BitOr(Box<BinaryExpr>),
+    BitXor(Box<BinaryExpr>),
+    BitAnd(Box<BinaryExpr>),
     Shift(Box<BinaryExpr>),
+    Term(Box<BinaryExpr>),
+    Factor(Box<BinaryExpr>),
     Unary(Box<UnaryExpr>),
-    FnCall(FnCall),
+    FnCall(Box<FnCall>),
     Cast(Box<Cast>),
-    Ref(Ref),
+    Ref(Box<Ref>),
     Primary(Box<PrimaryExpr>),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum PrimaryExpr {
     Grouping(Expr),
     Literal(Token),
     Id(Token),
-    StructField(Token, Token),
+    StructField(Expr, Token), //(struct or pointer to struct, field)
     EnumVariant(Token, Token),
-    ArrayAccess(Token, Expr)
+    ArrayAccess(Expr, Expr), //(array or pointer, index)
+    StructLit(Token, Vec<(Token, Expr)>), //(struct name, (field, value))
+    SizeOf(Token, SizeOfArg), //(sizeof, type or value)
+    AlignOf(Token, DeclrType), //(alignof, type)
+    OffsetOf(Token, DeclrType, Token), //(offsetof, struct type, field)
+    ArrayLit(Vec<Expr>),
+    ArrayRepeat(Expr, Expr), //[value; count], count is a constant expression
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum SizeOfArg {
+    Type(DeclrType),
+    Expr(Expr), //a bare name is a type unless a variable or const has that name
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct BinaryExpr {
     pub left: Expr,
     pub operator: Token,
     pub right: Expr,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct UnaryExpr {
     pub operator: Token,
     pub right: Expr
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Cast {
     pub value: Expr,
     pub to_type: DeclrType
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Ref {
     pub operator: Token,
-    pub right: Token,
+    pub right: Expr,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct FnCall {
-    pub name: Token,
+    pub callee: Expr, //a function name or anything of a function type
+    pub paren: Token,
     pub args: Vec<Expr>
 }
 
 pub fn new_expr(t: &'static str) -> Expr {
     match t {
         "Base" => Expr::Base,
-        "Assign" => Expr::Assign(Box::new(BinaryExpr{
-            left: new_expr("Equality"),
-            operator: Token {ttype: TokenType::Op("=".to_string()), pos: 0},
-            right: new_expr("Equality"),}
-        )),
-        "Equality" => Expr::Equality(Box::new(
-            BinaryExpr {
-                left: new_expr("Comp"),
-                operator: Token {ttype: TokenType::Arrow, pos: 0},
-                right: new_expr("Comp"),
-            }
-        )),
-        "Comp" => Expr::Comparison(Box::new(
-            BinaryExpr {
-                left: new_expr("Term"),
-                operator: Token {ttype: TokenType::Arrow, pos: 0},
-                right: new_expr("Term"),
-            }
-        )),
-        "Term" => Expr::Term(Box::new(
-            BinaryExpr {
-                left: new_expr("Shift"),
-                operator: Token {ttype: TokenType::Arrow, pos: 0},
-                right: new_expr("Shift"),
-            }
-        )),
-        "Shift" => Expr::Shift(Box::new(
-            BinaryExpr {
-                left: new_expr("Unary"),
-                operator: Token {ttype: TokenType::Arrow, pos: 0},
-                right: new_expr("Primary")
-            }
-        )),
-        "Unary" => Expr::Unary(Box::new(
-            UnaryExpr {
-                operator: Token {ttype: TokenType::Arrow, pos: 0},
-                right: new_expr("Primary"),
+        _ => panic!("new_expr invalid syntax -- {}", t)
+    }
+}
+
+//binding powers, a higher number binds tighter
+const ASSIGN: u8 = 1;
+const LOGIC_OR: u8 = 2;
+const LOGIC_AND: u8 = 3;
+const EQUALITY: u8 = 4;
+const COMP: u8 = 5;
+const BIT_OR: u8 = 6;
+const BIT_XOR: u8 = 7;
+const BIT_AND: u8 = 8;
+const SHIFT: u8 = 9;
+const TERM: u8 = 10;
+const FACTOR: u8 = 11;
+const UNARY: u8 = 12;
+const CAST: u8 = 13;
+const REF: u8 = 14;
+const POSTFIX: u8 = 15;
+
+#[derive(PartialEq)]
+enum Assoc {
+    Left,
+    Right,
+}
+
+struct InfixOp {
+    op: &'static str,
+    prec: u8,
+    assoc: Assoc,
+    make: fn(Box<BinaryExpr>) -> Expr,
+}
+
+//the operator token has already been consumed
+struct PrefixOp {
+    op: &'static str,
+    prec: u8,
+    parse: fn(&mut Parser, Token, u8) -> Result<Expr, ParseError>,
+}
+
+//the operator token is still at p.peek(0)
+struct PostfixOp {
+    op: &'static str,
+    prec: u8,
+    parse: fn(&mut Parser, Expr) -> Result<Expr, ParseError>,
+}
+
+static INFIX_OPS: [InfixOp; 19] = [
+    InfixOp {op: "=", prec: ASSIGN, assoc: Assoc::Right, make: Expr::Assign},
+    InfixOp {op: "||", prec: LOGIC_OR, assoc: Assoc::Left, make: Expr::LogicOr},
+    InfixOp {op: "&&", prec: LOGIC_AND, assoc: Assoc::Left, make: Expr::LogicAnd},
+    InfixOp {op: "==", prec: EQUALITY, assoc: Assoc::Left, make: Expr::Equality},
+    InfixOp {op: "!=", prec: EQUALITY, assoc: Assoc::Left, make: Expr::Equality},
+    InfixOp {op: "<", prec: COMP, assoc: Assoc::Left, make: Expr::Comparison},
+    InfixOp {op: ">", prec: COMP, assoc: Assoc::Left, make: Expr::Comparison},
+    InfixOp {op: "<=", prec: COMP, assoc: Assoc::Left, make: Expr::Comparison},
+    InfixOp {op: ">=", prec: COMP, assoc: Assoc::Left, make: Expr::Comparison},
+    InfixOp {op: "|", prec: BIT_OR, assoc: Assoc::Left, make: Expr::BitOr},
+    InfixOp {op: "^", prec: BIT_XOR, assoc: Assoc::Left, make: Expr::BitXor},
+    InfixOp {op: "&", prec: BIT_AND, assoc: Assoc::Left, make: Expr::BitAnd},
+    InfixOp {op: "<<", prec: SHIFT, assoc: Assoc::Left, make: Expr::Shift},
+    InfixOp {op: ">>", prec: SHIFT, assoc: Assoc::Left, make: Expr::Shift},
+    InfixOp {op: "+", prec: TERM, assoc: Assoc::Left, make: Expr::Term},
+    InfixOp {op: "-", prec: TERM, assoc: Assoc::Left, make: Expr::Term},
+    InfixOp {op: "*", prec: FACTOR, assoc: Assoc::Left, make: Expr::Factor},
+    InfixOp {op: "/", prec: FACTOR, assoc: Assoc::Left, make: Expr::Factor},
+    InfixOp {op: "%", prec: FACTOR, assoc: Assoc::Left, make: Expr::Factor},
+];
+
+static PREFIX_OPS: [PrefixOp; 5] = [
+    PrefixOp {op: "-", prec: UNARY, parse: parse_unary},
+    PrefixOp {op: "!", prec: UNARY, parse: parse_unary},
+    PrefixOp {op: "~", prec: UNARY, parse: parse_unary},
+    PrefixOp {op: "*", prec: REF, parse: parse_ref},
+    PrefixOp {op: "&", prec: REF, parse: parse_ref},
+];
+
+static POSTFIX_OPS: [PostfixOp; 4] = [
+    PostfixOp {op: "as", prec: CAST, parse: parse_cast},
+    PostfixOp {op: "(", prec: POSTFIX, parse: parse_call},
+    PostfixOp {op: "[", prec: POSTFIX, parse: parse_index},
+    PostfixOp {op: ".", prec: POSTFIX, parse: parse_field},
+];
+
+//the text operator tables are keyed by
+fn op_text(t: &TokenType) -> Option<&str> {
+    match t {
+        TokenType::Op(d) | TokenType::Cond(d) => Some(d.as_str()),
+        TokenType::ParenOpen => Some("("),
+        TokenType::SquareOpen => Some("["),
+        TokenType::Period => Some("."),
+        _ => None
+    }
+}
+
+//precedence climbing: parses operators that bind at least as tight as min_prec
+fn parse_expr(p: &mut Parser, min_prec: u8) -> Result<Expr, ParseError> {
+    let current = p.peek(0).ttype;
+    let prefix = op_text(&current).and_then(|t| PREFIX_OPS.iter().find(|o| o.op == t));
+
+    let mut left = match prefix {
+        Some(op) => {
+            let operator = p.peek(0);
+            p.advance();
+            (op.parse)(p, operator, op.prec)?
+        }
+        None => parse_primary(p)?
+    };
+
+    loop {
+        let current = p.peek(0).ttype;
+        let text = match op_text(&current) {
+            Some(t) => t,
+            None => break
+        };
+
+        if let Some(op) = POSTFIX_OPS.iter().find(|o| o.op == text) {
+            if op.prec < min_prec {
+                break
             }
-        )),
 
-        "FnCall" => {
-            Expr::FnCall(FnCall{
-                name: Token {ttype: TokenType::Arrow, pos: 0},
-                args: vec![]
-            }) 
+            left = (op.parse)(p, left)?;
+            continue;
         }
 
-        "Cast" => Expr::Cast(Box::new(Cast {
-            value: new_expr("Base"),
-            to_type: DeclrType::BasicType(BLANK_TOKEN),
-        })),
+        if let Some(op) = INFIX_OPS.iter().find(|o| o.op == text) {
+            if op.prec < min_prec {
+                break
+            }
 
-        "Ref" => Expr::Ref(Ref {
-            operator: BLANK_TOKEN,
-            right: BLANK_TOKEN }),
+            let operator = p.peek(0);
+            p.advance();
 
-        "Primary" => Expr::Primary(Box::new(PrimaryExpr::Literal(BLANK_TOKEN))),
-        _ => panic!("new_expr invalid syntax -- {}", t)
+            let right_prec = if op.assoc == Assoc::Right {op.prec} else {op.prec + 1};
+            let right = parse_expr(p, right_prec)?;
+
+            left = (op.make)(Box::new(BinaryExpr {
+                left,
+                operator,
+                right }));
+            continue;
+        }
+
+        break
     }
+
+    Ok(left)
 }
 
-impl Expr {    
-    pub fn parse(&mut self, p: &mut Parser) -> Result<Expr, &'static str> {
-        *self = match self {
-            Expr::Base => {
-                let mut e = new_expr("Assign");
-                e.parse(p)?
-            }
+fn parse_unary(p: &mut Parser, operator: Token, prec: u8) -> Result<Expr, ParseError> {
+    let right = parse_expr(p, prec)?;
 
-            Expr::Assign(_) => {
-                let mut e = new_expr("Equality").parse(p)?;
+    Ok(Expr::Unary(Box::new(UnaryExpr {
+        operator,
+        right })))
+}
 
-                if TokenType::Op("=".to_string()) == p.peek(0).ttype {
-                    p.advance();
-                    let right = new_expr("Base").parse(p)?;
+fn parse_ref(p: &mut Parser, operator: Token, prec: u8) -> Result<Expr, ParseError> {
+    let right = parse_expr(p, prec)?;
 
-                    e = Expr::Assign(Box::new(BinaryExpr {
-                        left: e,
-                        operator: Token {ttype: TokenType::Op("=".to_string()), pos: p.peek(-1).pos},
-                        right: right }))
-                }
-                
-                e
+    Ok(Expr::Ref(Box::new(Ref {
+        operator,
+        right })))
+}
+
+fn parse_cast(p: &mut Parser, value: Expr) -> Result<Expr, ParseError> {
+    p.advance();
+    let to_type = parse_type(p)?;
+
+    Ok(Expr::Cast(Box::new(Cast {
+        value,
+        to_type })))
+}
+
+fn parse_builtin(p: &mut Parser) -> Result<Expr, ParseError> {
+    let keyword = p.peek(0);
+    p.advance();
+    p.advance();
+
+    let builtin = match keyword.data().as_str() {
+        "sizeof" => {
+            //anything starting like this can only be a type
+            let type_starts = [TokenType::SquareOpen, TokenType::Key("@".to_string()), TokenType::Key("fn".to_string())];
+
+            if type_starts.contains(&p.peek(0).ttype) {
+                PrimaryExpr::SizeOf(keyword, SizeOfArg::Type(parse_type(p)?))
+            } else {
+                PrimaryExpr::SizeOf(keyword, SizeOfArg::Expr(new_expr("Base").parse(p)?))
             }
+        }
 
-            Expr::Equality(_) => {
-                let mut e = new_expr("Comp");
-                e.parse(p)?;
-                
-
-                while if let TokenType::Cond(d) = p.peek(0).ttype {
-                    d.as_str() == "==" || d.as_str() == "!="
-                } else {false} {
-                    let new_operator = p.peek(0);
-                    p.advance();
-
-                    let mut r = new_expr("Comp");
-                    r.parse(p)?;
-
-                    e = Expr::Equality(Box::new(
-                        BinaryExpr {
-                            left: e,
-                            operator: new_operator,
-                            right: r,
-                        }
-                    ));
-                }
-                e
+        "alignof" => PrimaryExpr::AlignOf(keyword, parse_type(p)?),
+
+        _ => {
+            let struct_type = parse_type(p)?;
+
+            if p.peek(0).ttype != TokenType::Comma {
+                return Err(p.error("Expected Comma after Struct Type", vec![TokenType::Comma]).hint("offsetof takes a struct and one of its fields: offsetof(Struct, field)"))
             }
+            p.advance();
 
-            Expr::Comparison(_) => {
-                let mut e = new_expr("Term");
-                e.parse(p)?;
-
-                while if let TokenType::Cond(d) = p.peek(0).ttype {
-                    d.as_str() == "<" || d.as_str() == ">" ||
-                    d.as_str() == "<=" || d.as_str() == ">="
-                } else {false} {
-                    
-                    let new_operator = p.peek(0);
-                    p.advance();
-
-                    let mut r = new_expr("Term");
-                    r.parse(p)?;
-
-                    e = Expr::Comparison(Box::new(
-                        BinaryExpr {
-                            left: e,
-                            operator: new_operator,
-                            right: r,
-                        }
-                    ));
-                }
-                e
+            if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
+                return Err(p.error("Expected Field Name", vec![TokenType::Id(String::new())]))
             }
-        
-            Expr::Term(_) => {
-                let mut e = new_expr("Shift");
-                e.parse(p)?;
-
-                while if let TokenType::Op(d) = p.peek(0).ttype {
-                    d.as_str() == "-" || d.as_str() == "+"
-                } else {false} {
-                    
-                    let new_operator = p.peek(0);
-                    p.advance();
-
-                    let mut r = new_expr("Shift");
-                    r.parse(p)?;
-
-                    e = Expr::Term(Box::new(
-                        BinaryExpr {
-                            left: e,
-                            operator: new_operator,
-                            right: r,
-                        }
-                    ));
-                }
-                e
+            let field = p.peek(0);
+            p.advance();
+
+            PrimaryExpr::OffsetOf(keyword, struct_type, field)
+        }
+    };
+
+    if p.peek(0).ttype != TokenType::ParenClose {
+        return Err(p.error("Expected Closing Parentheses", vec![TokenType::ParenClose]))
+    }
+    p.advance();
+
+    Ok(Expr::Primary(Box::new(builtin)))
+}
+
+fn parse_call(p: &mut Parser, left: Expr) -> Result<Expr, ParseError> {
+    let paren = p.peek(0);
+    p.advance();
+
+    let mut args: Vec<Expr> = vec![];
+
+    if p.peek(0).ttype != TokenType::ParenClose {
+        loop {
+            let arg = new_expr("Base").parse(p)?;
+            args.push(arg);
+
+            if p.peek(0).ttype == TokenType::ParenClose {
+                break
             }
-        
-            Expr::Shift(_) => {
-                let mut e = new_expr("Unary");
-                e.parse(p)?;
-
-                while if let TokenType::Op(d) = p.peek(0).ttype {
-                    d.as_str() == "<<" || d.as_str() == ">>"
-                } else {false} {
-                    
-                    let new_operator = p.peek(0);
-                    p.advance();
-
-                    let mut r = new_expr("Primary");
-                    r.parse(p)?;
-
-                    e = Expr::Term(Box::new(
-                        BinaryExpr {
-                            left: e,
-                            operator: new_operator,
-                            right: r,
-                        }
-                    ));
-                }
-                e
+
+            if p.peek(0).ttype != TokenType::Comma {
+                return Err(p.error("Expected Function Arguments to be Seperated by Commas", vec![TokenType::Comma, TokenType::ParenClose]))
             }
 
-            Expr::Unary(_) => 'b: {
-                let new_operator: Token;
-                if let TokenType::Op(d) = p.peek(0).ttype {
-                    if d.as_str() == "-" || d.as_str() == "!" {
-                        new_operator = p.peek(0);
-                        p.advance();
-                        
-                        let mut r = new_expr("FnCall");
-                        r.parse(p)?;
-                        
-                        break 'b Expr::Unary(Box::new(
-                            UnaryExpr {
-                                operator: new_operator,
-                                right: r,
-                            }
-                        ))
-                    } 
-                }
+            p.advance();
+        }
+    }
+    p.advance();
 
-                let mut e = new_expr("FnCall");
-                e.parse(p)?
-            }
-        
-            Expr::FnCall(_) =>  'b: {
-                if matches!(p.peek(0).ttype, TokenType::Id(_))
-                && p.peek(1).ttype == TokenType::ParenOpen {
-                    
-                    let fn_name = p.peek(0);
-                    let mut args: Vec<Expr> = vec![];
-
-                    p.advance();
-                    p.advance();
-
-                    if p.peek(0).ttype != TokenType::ParenClose {
-                        loop {
-                            let arg = new_expr("Base").parse(p)?;
-                            args.push(arg);
-                            
-                            if p.peek(0).ttype == TokenType::ParenClose {
-                                p.advance();
-                                break
-                            }
-    
-                            if p.peek(0).ttype != TokenType::Comma {
-                                return Err("Expected Function Arguments to be Seperated by Commas")
-                            }
-                            
-                            p.advance();
-                        }    
-                    } else {
-                        p.advance();
-                    }
+    Ok(Expr::FnCall(Box::new(FnCall {
+        callee: left,
+        paren,
+        args })))
+}
 
-                    break 'b Expr::FnCall(FnCall {
-                        name: fn_name,
-                        args: args })
-                }
+fn parse_index(p: &mut Parser, left: Expr) -> Result<Expr, ParseError> {
+    p.advance();
 
-                new_expr("Cast").parse(p)?
-            }
+    let array_index = new_expr("Base").parse(p)?;
 
-            Expr::Cast(_) => 'b: {
-                let v = new_expr("Ref").parse(p)?;
+    if !matches!(p.peek(0).ttype, TokenType::SquareClose) {
+        return Err(p.error("Expected Closing Square Bracket after Array Access", vec![TokenType::SquareClose]))
+    }
+    p.advance();
 
-                if p.peek(0).ttype == TokenType::Op("as".to_string()) {
-                    p.advance();
+    Ok(Expr::Primary(Box::new(PrimaryExpr::ArrayAccess(left, array_index))))
+}
 
-                    let to_type = parse_type(p)?;
+fn parse_field(p: &mut Parser, left: Expr) -> Result<Expr, ParseError> {
+    p.advance();
 
-                    break 'b Expr::Cast(Box::new(
-                        Cast {
-                            value: v,
-                            to_type: to_type
-                        }
-                    ))
-                }
+    if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
+        return Err(p.error("Expected Field Name after Period", vec![TokenType::Id(String::new())]))
+    }
 
-                v
-            }
+    let field = p.peek(0);
+    p.advance();
 
-            Expr::Ref(_) => 'b: {
-                if p.peek(0).ttype == TokenType::Op("*".to_string()) || 
-                p.peek(0).ttype == TokenType::Op("&".to_string()){
-                    p.advance();
-                    if let TokenType::Id(_) = p.peek(0).ttype {
-                        p.advance();
-                        break 'b Expr::Ref(Ref {
-                            operator: p.peek(-2),
-                            right: p.peek(-1)
-                        })
-                    }
-                    return Err("Expected Identifier to Reference")
-                }
+    Ok(Expr::Primary(Box::new(PrimaryExpr::StructField(left, field))))
+}
 
-                new_expr("Primary").parse(p)?
-            }
+//Name { field: value, ... }
+fn parse_struct_literal(p: &mut Parser) -> Result<Expr, ParseError> {
+    let name = p.peek(0);
+    p.advance();
+    p.advance();
 
-            Expr::Primary(_) => 'b: {
-                
-                //Grouping
-                if let TokenType::ParenOpen = p.peek(0).ttype {
-                    let mut e = new_expr("Base");
-                    p.advance();
-                    e.parse(p)?;
+    let mut fields: Vec<(Token, Expr)> = vec![];
 
-                    match p.peek(0).ttype {
-                        TokenType::ParenClose => {}
-                        _ => return Err("Expected Closing Parentheses")
+    while p.peek(0).ttype != TokenType::CurlyClose {
+        if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
+            return Err(p.error("Expected Field Name in Struct Literal", vec![TokenType::Id(String::new()), TokenType::CurlyClose]))
+        }
+        let field = p.peek(0);
+        p.advance();
 
-                    }
+        if p.peek(0).ttype != TokenType::Col {
+            return Err(p.error("Expected Colon after Field Name", vec![TokenType::Col]))
+        }
+        p.advance();
 
-                    p.advance();
+        let value = new_expr("Base").parse(p)?;
+        fields.push((field, value));
 
-                    break 'b Expr::Primary(Box::new(
-                        PrimaryExpr::Grouping(e)
-                    ))
+        match p.peek(0).ttype {
+            TokenType::Comma => p.advance(),
+            TokenType::CurlyClose => {}
+            _ => return Err(p.error("Expected Comma between Struct Literal Fields", vec![TokenType::Comma, TokenType::CurlyClose]))
+        }
+    }
+    p.advance();
 
-                }
+    Ok(Expr::Primary(Box::new(PrimaryExpr::StructLit(name, fields))))
+}
 
-                //redo this
-                //Enum Variant
-                if matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                    if let Some(col1) = p.peek_forward(1) {
-                        if col1.ttype == TokenType::Col {
-                            if let Some(col2) = p.peek_forward(2) {
-                                if col2.ttype == TokenType::Col {
-                                    if let Some(id2) = p.peek_forward(3) {
-                                        if matches!(id2.ttype, TokenType::Id(_)) {
-                                            p.advance();
-                                            p.advance();
-                                            p.advance();
-                                            p.advance();
-                                            break 'b Expr::Primary(Box::new(PrimaryExpr::EnumVariant(p.peek_forward(-4).unwrap(), p.peek_forward(-1).unwrap())))
-                                        }
-                                        
-                                    }
-                                }
-                                
-                            }
-                        }
-                        
-                    }
-                }
-                
-                
-                //Struct Field
-                if matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                    if let Some(period) = p.peek_forward(1) {
-                        if period.ttype == TokenType::Period {
-                            if let Some(id2) = p.peek_forward(2) {
-                                if matches!(id2.ttype, TokenType::Id(_)) {
-                                    p.advance();
-                                    p.advance();
-                                    p.advance();
-                                    break 'b Expr::Primary(Box::new(PrimaryExpr::StructField(p.peek_forward(-3).unwrap(), p.peek_forward(-1).unwrap())))    
-                                }
-                            }
-                        }
-                    }
-                        
-                }
+//[a, b, c] or [value; count]
+fn parse_array_literal(p: &mut Parser) -> Result<Expr, ParseError> {
+    p.advance();
 
-                //Array Access
-                if matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                    if let Some(forward) = p.peek_forward(1) {
-                        if matches!(forward.ttype, TokenType::SquareOpen) {
-                            let array_name = p.peek(0);
-                            p.advance();
-                            p.advance();
-                            let array_index = new_expr("Base").parse(p)?;
-    
-                            if !matches!(p.peek(0).ttype, TokenType::SquareClose) {
-                                return Err("Expected Closing Square Bracket after Array Access")
-                            }
-                            p.advance();
-                            break 'b Expr::Primary(Box::new(PrimaryExpr::ArrayAccess(array_name, array_index)))
-                        }
-                    }
-                }
+    let first = new_expr("Base").parse(p)?;
 
-                //ID or Literal if this point is reached
-                let e = match p.peek(0).ttype {
-                    TokenType::Lit(_) => {
-                        Expr::Primary(
-                            Box::new(PrimaryExpr::Literal(p.peek(0)))
-                        )
-                    }
+    if p.peek(0).ttype == TokenType::SemiCol {
+        p.advance();
 
-                    TokenType::Id(_) => {
-                        Expr::Primary(
-                            Box::new(PrimaryExpr::Id(p.peek(0)))
-                        )
-                    }
-                    _ => return Err("Expected Expression")
-                };
-                p.advance();
-                e
+        let count = new_expr("Base").parse(p)?;
+
+        if p.peek(0).ttype != TokenType::SquareClose {
+            return Err(p.error("Expected Closing Square Bracket after Array Literal", vec![TokenType::SquareClose]))
+        }
+        p.advance();
+
+        return Ok(Expr::Primary(Box::new(PrimaryExpr::ArrayRepeat(first, count))))
+    }
+
+    let mut elements = vec![first];
+
+    loop {
+        match p.peek(0).ttype {
+            TokenType::SquareClose => break,
+            TokenType::Comma => p.advance(),
+            _ => return Err(p.error("Expected Comma between Array Elements", vec![TokenType::Comma, TokenType::SquareClose]))
+        }
+
+        //trailing comma
+        if p.peek(0).ttype == TokenType::SquareClose {
+            break
+        }
+
+        elements.push(new_expr("Base").parse(p)?);
+    }
+    p.advance();
+
+    Ok(Expr::Primary(Box::new(PrimaryExpr::ArrayLit(elements))))
+}
+
+//literals, names, enum variants, groupings and struct or array literals
+fn parse_primary(p: &mut Parser) -> Result<Expr, ParseError> {
+    if let TokenType::ParenOpen = p.peek(0).ttype {
+        p.advance();
+        let e = new_expr("Base").parse(p)?;
+
+        if p.peek(0).ttype != TokenType::ParenClose {
+            return Err(p.error("Expected Closing Parentheses", vec![TokenType::ParenClose]))
+        }
+        p.advance();
+
+        return Ok(Expr::Primary(Box::new(PrimaryExpr::Grouping(e))))
+    }
+
+    //Enum::Variant
+    if matches!(p.peek(0).ttype, TokenType::Id(_))
+    && p.peek(1).ttype == TokenType::Col
+    && p.peek(2).ttype == TokenType::Col
+    && matches!(p.peek(3).ttype, TokenType::Id(_)) {
+        let enum_name = p.peek(0);
+        let variant = p.peek(3);
+        for _ in 0..4 {
+            p.advance();
+        }
+
+        return Ok(Expr::Primary(Box::new(PrimaryExpr::EnumVariant(enum_name, variant))))
+    }
+
+    //Name { field: is never a block, no statement starts with an identifier and a colon
+    //and Name { Enum::Variant is the start of a match
+    //Name {} is left out on purpose, it is also how if flag {} and while ready {} begin
+    //so an empty struct can only be written as a zeroed static or a let without a value
+    if matches!(p.peek(0).ttype, TokenType::Id(_))
+    && p.peek(1).ttype == TokenType::CurlyOpen
+    && matches!(p.peek(2).ttype, TokenType::Id(_))
+    && p.peek(3).ttype == TokenType::Col
+    && p.peek(4).ttype != TokenType::Col {
+        return parse_struct_literal(p)
+    }
+
+    if p.peek(0).ttype == TokenType::SquareOpen {
+        return parse_array_literal(p)
+    }
+
+    //sizeof, alignof and offsetof are only keywords when followed by parentheses
+    let builtins = ["sizeof", "alignof", "offsetof"].map(|b| TokenType::Id(b.to_string()));
+    if builtins.contains(&p.peek(0).ttype) && p.peek(1).ttype == TokenType::ParenOpen {
+        return parse_builtin(p)
+    }
+
+    let e = match p.peek(0).ttype {
+        TokenType::Lit(_) | TokenType::Char(_) | TokenType::Str(_) => {
+            Expr::Primary(Box::new(PrimaryExpr::Literal(p.peek(0))))
+        }
+
+        TokenType::Id(_) => {
+            Expr::Primary(Box::new(PrimaryExpr::Id(p.peek(0))))
+        }
+        _ => return Err(p.error("Expected Expression", vec![]))
+    };
+    p.advance();
+
+    Ok(e)
+}
+
+impl Expr {
+    pub fn parse(&mut self, p: &mut Parser) -> Result<Expr, ParseError> {
+        *self = parse_expr(p, ASSIGN)?;
+        Ok(self.clone())
+    }
+
+    //the token diagnostics about this expression point at
+    pub fn get_token(&self) -> Token {
+        match self {
+            Expr::Base => BLANK_TOKEN,
+            Expr::Assign(e) | Expr::LogicOr(e) | Expr::LogicAnd(e) |
+            Expr::Equality(e) | Expr::Comparison(e) |
+            Expr::BitOr(e) | Expr::BitXor(e) | Expr::BitAnd(e) |
+            Expr::Shift(e) | Expr::Term(e) | Expr::Factor(e) => e.operator.clone(),
+            Expr::Unary(e) => e.operator.clone(),
+            Expr::FnCall(call) => call.callee.get_token(),
+            Expr::Cast(c) => c.value.get_token(),
+            Expr::Ref(r) => r.operator.clone(),
+            Expr::Primary(p) => match &**p {
+                PrimaryExpr::Grouping(e) => e.get_token(),
+                PrimaryExpr::Literal(t) | PrimaryExpr::Id(t) => t.clone(),
+                PrimaryExpr::StructField(_, field) => field.clone(),
+                PrimaryExpr::EnumVariant(_, variant) => variant.clone(),
+                PrimaryExpr::ArrayAccess(array, _) => array.get_token(),
+                PrimaryExpr::StructLit(name, _) => name.clone(),
+                PrimaryExpr::SizeOf(keyword, _) | PrimaryExpr::AlignOf(keyword, _) |
+                PrimaryExpr::OffsetOf(keyword, _, _) => keyword.clone(),
+                PrimaryExpr::ArrayLit(elements) => elements[0].get_token(),
+                PrimaryExpr::ArrayRepeat(value, _) => value.get_token(),
             }
-        };
-        //println!("\n{}\n", self);
-        return Ok(self.clone())
+        }
+    }
+
+    //a bare name could also be read as a type
+    pub fn as_type_name(&self) -> Option<DeclrType> {
+        match self {
+            Expr::Primary(p) => match &**p {
+                PrimaryExpr::Id(id) => Some(DeclrType::BasicType(id.clone())),
+                _ => None
+            },
+            _ => None
+        }
+    }
+
+    //whether the expression names a memory location that can be referenced
+    pub fn is_place(&self) -> bool {
+        match self {
+            Expr::Ref(r) => r.operator.data() == "*",
+            Expr::Primary(p) => match &**p {
+                PrimaryExpr::Grouping(e) => e.is_place(),
+                PrimaryExpr::Id(_) | PrimaryExpr::StructField(_, _) | PrimaryExpr::ArrayAccess(_, _) => true,
+                _ => false
+            },
+            _ => false
+        }
     }
 }
 
@@ -455,15 +581,15 @@ impl std::fmt::Display for Expr {
         match self {
             Self::Base => write!(f, ""),
             Self::Assign(d) => write!(f, "({} = {})", d.left, d.right),
-            Self::Equality(d) => write!(f, "({} {} {})", d.left, d.operator.ttype, d.right),
-            Self::Comparison(d) => write!(f, "({} {} {})", d.left, d.operator.ttype, d.right),
-            Self::Term(d) => write!(f, "({} {} {})", d.left, d.operator.ttype, d.right),
-            Self::Shift(d) => write!(f, "({} {} {})", d.left, d.operator.ttype, d.right),
+            Self::LogicOr(d) | Self::LogicAnd(d) |
+            Self::Equality(d) | Self::Comparison(d) |
+            Self::BitOr(d) | Self::BitXor(d) | Self::BitAnd(d) |
+            Self::Shift(d) | Self::Term(d) | Self::Factor(d) => write!(f, "({} {} {})", d.left, d.operator.ttype, d.right),
             Self::Unary(d) => write!(f, "({} {})", d.operator.ttype, d.right),
             Self::Cast(d) => write!(f, "({} cast to {})", d.value, d.to_type),
-            Self::Ref(d) => write!(f, "{} reference Op on {}", d.operator.data(), d.right.data()),
+            Self::Ref(d) => write!(f, "{} reference Op on {}", d.operator.data(), d.right),
             Self::FnCall(d) => {
-                write!(f, "function call of {}  params:", d.name.ttype)?;
+                write!(f, "function call of {}  params:", d.callee)?;
 
                 for arg in &d.args {
                     write!(f, "\narg: {}", arg)?;
@@ -478,10 +604,133 @@ impl std::fmt::Display for Expr {
                     PrimaryExpr::Literal(v) => write!(f, "{}", v.ttype),
                     PrimaryExpr::Id(v) => write!(f, "{}", v.ttype), 
                     PrimaryExpr::EnumVariant(t1, t2) => write!(f, "varint {} of enum {}", t2.ttype,  t1.ttype),
-                    PrimaryExpr::StructField(s, field) => write!(f, "field {} of struct {}", field.ttype, s.ttype),
-                    PrimaryExpr::ArrayAccess(name, index) => write!(f, "Access of Array {} at index {}", name.ttype, index)
+                    PrimaryExpr::StructField(s, field) => write!(f, "field {} of struct {}", field.ttype, s),
+                    PrimaryExpr::ArrayAccess(array, index) => write!(f, "Access of Array {} at index {}", array, index),
+                    PrimaryExpr::StructLit(name, fields) => {
+                        let fields: Vec<String> = fields.iter().map(|(n, v)| format!("{}: {}", n.data(), v)).collect();
+                        write!(f, "{} {{{}}}", name.data(), fields.join(", "))
+                    }
+                    PrimaryExpr::ArrayLit(elements) => {
+                        let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                        write!(f, "[{}]", elements.join(", "))
+                    }
+                    PrimaryExpr::ArrayRepeat(value, count) => write!(f, "[{}; {}]", value, count),
+                    PrimaryExpr::SizeOf(_, SizeOfArg::Type(t)) => write!(f, "sizeof({})", t),
+                    PrimaryExpr::SizeOf(_, SizeOfArg::Expr(e)) => write!(f, "sizeof({})", e),
+                    PrimaryExpr::AlignOf(_, t) => write!(f, "alignof({})", t),
+                    PrimaryExpr::OffsetOf(_, t, field) => write!(f, "offsetof({}, {})", t, field.data()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    //binary levels loosest first, matching the table at the top of this file
+    static LEVELS: [&[&str]; 11] = [
+        &["="],
+        &["||"],
+        &["&&"],
+        &["==", "!="],
+        &["<", ">", "<=", ">="],
+        &["|"],
+        &["^"],
+        &["&"],
+        &["<<", ">>"],
+        &["+", "-"],
+        &["*", "/", "%"],
+    ];
+
+    fn parse(text: &str) -> String {
+        let tokens = Lexer::new(text.to_string(), 0).lex();
+        let mut p = Parser::new(tokens);
+        let e = new_expr("Base").parse(&mut p).unwrap();
+
+        assert_eq!(p.peek(0).ttype, TokenType::EOF, "{} was not fully parsed", text);
+        e.to_string()
+    }
+
+    #[test]
+    fn binary_levels() {
+        for (i, loose) in LEVELS.iter().enumerate() {
+            for tight in &LEVELS[i + 1..] {
+                for lo in loose.iter() {
+                    for hi in tight.iter() {
+                        assert_eq!(parse(&format!("a {} b {} c", lo, hi)), format!("(a {} (b {} c))", lo, hi));
+                        assert_eq!(parse(&format!("a {} b {} c", hi, lo)), format!("((a {} b) {} c)", hi, lo));
+                    }
                 }
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn associativity() {
+        for level in &LEVELS[1..] {
+            for first in level.iter() {
+                for second in level.iter() {
+                    assert_eq!(parse(&format!("a {} b {} c", first, second)), format!("((a {} b) {} c)", first, second));
+                }
+            }
+        }
+
+        assert_eq!(parse("a = b = c"), "(a = (b = c))");
+    }
+
+    #[test]
+    fn prefix() {
+        for op in ["-", "!", "~"] {
+            assert_eq!(parse(&format!("{}a * b", op)), format!("(({} a) * b)", op));
+            assert_eq!(parse(&format!("{}{}a", op, op)), format!("({} ({} a))", op, op));
+        }
+
+        assert_eq!(parse("*p + 1"), "(* reference Op on p + 1)");
+        assert_eq!(parse("&v == p"), "(& reference Op on v == p)");
+        assert_eq!(parse("a * -b"), "(a * (- b))");
+    }
+
+    #[test]
+    fn cast() {
+        assert_eq!(parse("a + b as u16"), "(a + (b cast to u16))");
+        assert_eq!(parse("-a as u16"), "(- (a cast to u16))");
+        assert_eq!(parse("*p as u16"), "(* reference Op on p cast to u16)");
+        assert_eq!(parse("a as u8 as u16"), "((a cast to u8) cast to u16)");
+    }
+
+    #[test]
+    fn postfix() {
+        assert_eq!(parse("-a[i] + s.x"), "((- Access of Array a at index i) + field x of struct s)");
+        assert_eq!(parse("a[i + 1] * 2"), "(Access of Array a at index (i + 1) * 2)");
+        assert_eq!(parse("f(x) as u16"), "(function call of f  params:\narg: x cast to u16)");
+        assert_eq!(parse("-f()"), "(- function call of f  params:)");
+    }
+
+    #[test]
+    fn postfix_chains() {
+        assert_eq!(parse("a.b.c"), "field c of struct field b of struct a");
+        assert_eq!(parse("arr[i].x"), "field x of struct Access of Array arr at index i");
+        assert_eq!(parse("grid[y][x]"), "Access of Array Access of Array grid at index y at index x");
+        assert_eq!(parse("&s.field"), "& reference Op on field field of struct s");
+        assert_eq!(parse("*(p + 1)"), "* reference Op on ((p + 1))");
+        assert_eq!(parse("get()[0]"), "Access of Array function call of get  params: at index 0");
+        assert_eq!(parse("*p.x as u16"), "(* reference Op on field x of struct p cast to u16)");
+        assert_eq!(parse("handlers[i](x)"), "function call of Access of Array handlers at index i  params:\narg: x");
+        assert_eq!(parse("pick()(x)"), "function call of function call of pick  params:  params:\narg: x");
+    }
+
+    #[test]
+    fn primary() {
+        assert_eq!(parse("(a + b) * c"), "(((a + b)) * c)");
+        assert_eq!(parse("Color::Red == c"), "(varint Red of enum Color == c)");
+        assert_eq!(parse("Point { x: 1, y: a + 2, }.x"), "field x of struct Point {x: 1, y: (a + 2)}");
+        assert_eq!(parse("[1, 2, 3][i]"), "Access of Array [1, 2, 3] at index i");
+        assert_eq!(parse("[0; 16]"), "[0; 16]");
+        assert_eq!(parse("sizeof(@u8) * 2"), "(sizeof(Pointer at u8) * 2)");
+        assert_eq!(parse("sizeof(buf[0])"), "sizeof(Access of Array buf at index 0)");
+        assert_eq!(parse("offsetof(Point, y) + alignof(u16)"), "(offsetof(Point, y) + alignof(u16))");
+    }
+}
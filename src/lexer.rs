@@ -1,139 +1,453 @@
-use fancy_regex::Regex; 
 use crate::definitions::*;
 
 pub struct Lexer {
     data: String,
-    pub ptr: usize
+    file: FileId,
+    pub ptr: usize,
+    pub errors: Vec<LexError>,
 }
 
-impl Lexer {
-    pub fn new(text: String) -> Result<Self, ()> {
-        if !text.is_ascii() {
-            return Err(());
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    pub span: Span,
+    pub kind: LexErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexErrorKind {
+    UnexpectedChar(char),
+    InvalidNumber,
+    InvalidEscape,
+    InvalidChar,
+    UnterminatedChar,
+    UnterminatedStr,
+    UnterminatedComment,
+}
+
+impl std::fmt::Display for LexErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedChar(c) => write!(f, "Unexpected Character {:?}", c),
+            Self::InvalidNumber => write!(f, "Invalid Number Literal"),
+            Self::InvalidEscape => write!(f, "Invalid Escape Sequence"),
+            Self::InvalidChar => write!(f, "Char Literal must be a single ASCII Character"),
+            Self::UnterminatedChar => write!(f, "Unterminated Char Literal"),
+            Self::UnterminatedStr => write!(f, "Unterminated String Literal"),
+            Self::UnterminatedComment => write!(f, "Unterminated Block Comment"),
         }
-        
-        Ok( Lexer {
+    }
+}
+
+pub static KEYWORDS: [&str; 21] = [
+    "let",
+    "const",
+    "static",
+    "mut",
+    "type",
+    "if",
+    "fn",
+    "else",
+    "while",
+    "loop",
+    "for",
+    "in",
+    "match",
+    "return",
+    "continue",
+    "struct",
+    "enum",
+    "break",
+    "asm",
+    "extern",
+    "export",
+];
+
+//longest operators first so that e.g. << is never lexed as two <
+static OPERATORS: [(&str, bool); 21] = [
+    //(operator, is condition)
+    ("<<", false),
+    (">>", false),
+    ("||", true),
+    ("&&", true),
+    ("<=", true),
+    (">=", true),
+    ("==", true),
+    ("!=", true),
+    ("=", false),
+    ("+", false),
+    ("-", false),
+    ("*", false),
+    ("/", false),
+    ("%", false),
+    ("&", false),
+    ("|", false),
+    ("^", false),
+    ("~", false),
+    ("!", false),
+    ("<", true),
+    (">", true),
+];
+
+impl Lexer {
+    pub fn new(text: String, file: FileId) -> Self {
+        Lexer {
             data: text,
+            file,
             ptr: 0,
-        })
-    }
-
-    
-    pub fn lex(&mut self) -> Result<Vec<Token>, usize> {
-        let reg_key = Regex::new(r"(?x)
-        ^let |
-        ^if |
-        ^fn |
-        ^else |
-        ^while |
-        ^loop |
-        ^for |
-        ^return |
-        ^continue |
-        ^struct |
-        ^enum |
-        ^ @ |
-        ^break").unwrap();
-
-        let reg_op = Regex::new(r"(?x)
-        ^ as(?=\s) |
-        ^ \= (?!\=) |
-        ^ \+ |
-        ^ \- |
-        ^ \& |
-        ^ \* |
-        ^ << |
-        ^ >> |
-        ^ \|(?!\|) | #single pipe
-        ^ \! |
-        ^ \~\\").unwrap();
-
-        let reg_cond = Regex::new(r"(?x)
-        ^ \|\| |
-        ^ \&\& |
-        ^ <\= |
-        ^ >= |
-        ^ < (?!<) |
-        ^ > (?!>) |
-        ^ \=\=").unwrap();
-
-        let reg_id = Regex::new(r"^[_[[:alpha:]]][_@[[:alnum:]]]*").unwrap();
-
-        let reg_lit = Regex::new(r"^-?\d+(?![[:alpha:]])").unwrap();
+            errors: vec![],
+        }
+    }
 
+    //never fails, every problem is pushed to self.errors and leaves an Error token behind
+    pub fn lex(&mut self) -> Vec<Token> {
         let mut token_list: Vec<Token> = vec![];
 
-        while self.data.as_bytes()[self.ptr] != b'\0' {
-            self.skip_whitespace();
+        loop {
+            self.skip_trivia();
 
-            //println!("ptr: {}   char: {}", self.ptr, self.data.as_bytes()[self.ptr] as char);
+            if self.at_end() {
+                break;
+            }
 
-            if self.ptr < self.data.len() - 2 && &self.data.as_str()[self.ptr..self.ptr+2] == "->" {
-                token_list.push(Token {
-                    ttype: TokenType::Arrow,
-                    pos: self.ptr
-                });
-                self.ptr += 2;
-                continue;
+            let start = self.ptr;
+
+            let ttype = match self.next_token() {
+                Ok(t) => t,
+                Err(kind) => {
+                    if self.ptr == start {
+                        self.skip_char();
+                    }
+
+                    self.error(start, kind);
+                    TokenType::Error
+                }
+            };
+
+            token_list.push(Token {ttype, span: Span::new(self.file, start, self.ptr)});
+        }
+
+        token_list.push(Token {ttype: TokenType::EOF, span: Span::new(self.file, self.ptr, self.ptr)});
+        token_list
+    }
+
+    fn next_token(&mut self) -> Result<TokenType, LexErrorKind> {
+        let t = match self.peek(0) {
+            b'(' => {self.ptr += 1; TokenType::ParenOpen}
+            b')' => {self.ptr += 1; TokenType::ParenClose}
+            b'[' => {self.ptr += 1; TokenType::SquareOpen}
+            b']' => {self.ptr += 1; TokenType::SquareClose}
+            b'{' => {self.ptr += 1; TokenType::CurlyOpen}
+            b'}' => {self.ptr += 1; TokenType::CurlyClose}
+            b';' => {self.ptr += 1; TokenType::SemiCol}
+            b':' => {self.ptr += 1; TokenType::Col}
+            b',' => {self.ptr += 1; TokenType::Comma}
+            b'.' if self.peek(1) == b'.' && self.peek(2) == b'=' => {self.ptr += 3; TokenType::RangeIncl}
+            b'.' if self.peek(1) == b'.' => {self.ptr += 2; TokenType::Range}
+            b'.' => {self.ptr += 1; TokenType::Period}
+            b'@' => {self.ptr += 1; TokenType::Key("@".to_string())}
+
+            b'-' if self.peek(1) == b'>' => {self.ptr += 2; TokenType::Arrow}
+            b'=' if self.peek(1) == b'>' => {self.ptr += 2; TokenType::FatArrow}
+            b'/' if self.at_doc_comment() => self.lex_doc_comment(),
+
+            b'\'' if self.at_label() => self.lex_label(),
+            b'\'' => TokenType::Char(self.lex_char()?),
+            b'"' => TokenType::Str(self.lex_str()?),
+
+            b'0'..=b'9' => self.lex_number()?,
+
+            c if c == b'_' || c.is_ascii_alphabetic() => self.lex_word(),
+
+            _ => self.lex_operator()?,
+        };
+
+        Ok(t)
+    }
+
+    fn error(&mut self, start: usize, kind: LexErrorKind) {
+        self.errors.push(LexError {
+            span: Span::new(self.file, start, self.ptr),
+            kind });
+    }
+
+    fn at_end(&self) -> bool {
+        self.ptr >= self.data.len()
+    }
+
+    //returns 0 past the end of the text
+    fn peek(&self, n: usize) -> u8 {
+        *self.data.as_bytes().get(self.ptr + n).unwrap_or(&0)
+    }
+
+    //steps over a whole UTF-8 character
+    fn skip_char(&mut self) {
+        self.ptr += self.current_char().map_or(1, |c| c.len_utf8());
+    }
+
+    fn current_char(&self) -> Option<char> {
+        self.data.get(self.ptr..).and_then(|s| s.chars().next())
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &str {
+        let start = self.ptr;
+        while !self.at_end() && f(self.peek(0)) {
+            self.ptr += 1;
+        }
+        &self.data[start..self.ptr]
+    }
+
+    //'a' is a char literal, 'a or 'outer is a loop label, 'ab' is a bad char literal
+    fn at_label(&self) -> bool {
+        let c = self.peek(1);
+        if c != b'_' && !c.is_ascii_alphabetic() {
+            return false
+        }
+
+        let mut n = 2;
+        while self.peek(n) == b'_' || self.peek(n).is_ascii_alphanumeric() {
+            n += 1;
+        }
+        self.peek(n) != b'\''
+    }
+
+    fn lex_label(&mut self) -> TokenType {
+        self.ptr += 1;
+        let name = self.take_while(|c| c == b'_' || c.is_ascii_alphanumeric());
+        TokenType::Label(name.to_string())
+    }
+
+    //identifiers are maximal munch, keywords are whole words only
+    fn lex_word(&mut self) -> TokenType {
+        let word = self.take_while(|c| c == b'_' || c == b'@' || c.is_ascii_alphanumeric());
+
+        if word == "as" {
+            return TokenType::Op(word.to_string())
+        }
+
+        if KEYWORDS.contains(&word) {
+            return TokenType::Key(word.to_string())
+        }
+
+        TokenType::Id(word.to_string())
+    }
+
+    //hex, binary, octal or decimal digits with optional _ separators and a type suffix
+    fn lex_number(&mut self) -> Result<TokenType, LexErrorKind> {
+        let text = self.take_while(|c| c == b'_' || c.is_ascii_alphanumeric());
+
+        let mut digits = text;
+        for suffix in ["u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64"] {
+            if let Some(d) = digits.strip_suffix(suffix) {
+                digits = d;
+                break;
             }
+        }
 
-            if let Some(m) = reg_key.find(&self.data.as_str()[self.ptr..]).unwrap() {
-                token_list.push(Token{ttype: TokenType::Key(m.as_str().to_string()), pos: self.ptr});
-                self.ptr += m.as_str().len();
-                continue;
+        let (radix, digits) = match digits.get(..2) {
+            Some("0x") => (16, &digits[2..]),
+            Some("0b") => (2, &digits[2..]),
+            Some("0o") => (8, &digits[2..]),
+            _ => (10, digits)
+        };
+
+        let valid = digits.bytes().any(|c| c != b'_')
+            && digits.bytes().all(|c| c == b'_' || (c as char).is_digit(radix));
+
+        if !valid {
+            return Err(LexErrorKind::InvalidNumber)
+        }
+
+        Ok(TokenType::Lit(text.to_string()))
+    }
+
+    fn lex_operator(&mut self) -> Result<TokenType, LexErrorKind> {
+        let rest = &self.data.as_bytes()[self.ptr..];
+
+        for (op, is_cond) in OPERATORS {
+            if rest.starts_with(op.as_bytes()) {
+                self.ptr += op.len();
+
+                if is_cond {
+                    return Ok(TokenType::Cond(op.to_string()))
+                }
+                return Ok(TokenType::Op(op.to_string()))
             }
+        }
 
-            if let Some(m) = reg_op.find(&self.data.as_str()[self.ptr..]).unwrap() {
-                token_list.push(Token{ttype: TokenType::Op(m.as_str().to_string()), pos: self.ptr});
-                self.ptr += m.as_str().len();
-                continue;
+        let c = self.current_char().unwrap_or('\0');
+        self.skip_char();
+        Err(LexErrorKind::UnexpectedChar(c))
+    }
+
+    /// and //! but not //// which is a plain comment
+    fn at_doc_comment(&self) -> bool {
+        self.peek(0) == b'/' && self.peek(1) == b'/' &&
+        ((self.peek(2) == b'/' && self.peek(3) != b'/') || self.peek(2) == b'!')
+    }
+
+    fn lex_doc_comment(&mut self) -> TokenType {
+        let inner = self.peek(2) == b'!';
+        self.ptr += 3;
+
+        let start = self.ptr;
+        self.skip_line_comment();
+
+        let text = self.data[start..self.ptr].trim_end_matches('\r');
+        let text = text.strip_prefix(' ').unwrap_or(text).to_string();
+
+        if inner {
+            return TokenType::InnerDoc(text)
+        }
+        TokenType::Doc(text)
+    }
+
+    //'A', '\n', '\x1b'
+    fn lex_char(&mut self) -> Result<u8, LexErrorKind> {
+        self.ptr += 1;
+
+        let c = match self.peek(0) {
+            b'\\' => self.lex_escape(),
+            b'\'' => {self.ptr += 1; return Err(LexErrorKind::InvalidChar)}
+            b'\n' | 0 => return Err(LexErrorKind::UnterminatedChar),
+            c if c.is_ascii() => {self.ptr += 1; Ok(c)}
+            _ => {self.skip_char(); Err(LexErrorKind::InvalidChar)}
+        };
+
+        if self.peek(0) != b'\'' {
+            //'ab' is one bad literal rather than a literal followed by garbage
+            let rest = self.data[self.ptr..].lines().next().unwrap_or("");
+            if let Some(close) = rest.find('\'') {
+                self.ptr += close + 1;
+                return Err(LexErrorKind::InvalidChar)
             }
 
-            if let Some(m) = reg_cond.find(&self.data.as_str()[self.ptr..]).unwrap() {
-                token_list.push(Token{ttype: TokenType::Cond(m.as_str().to_string()), pos: self.ptr});
-                self.ptr += m.as_str().len();
-                continue;
+            return Err(LexErrorKind::UnterminatedChar)
+        }
+        self.ptr += 1;
+
+        c
+    }
+
+    //"text", with the same escapes as char literals
+    //any UTF-8 text is allowed and stored as its bytes
+    fn lex_str(&mut self) -> Result<Vec<u8>, LexErrorKind> {
+        self.ptr += 1;
+
+        let mut bytes = vec![];
+        loop {
+            if self.at_end() {
+                return Err(LexErrorKind::UnterminatedStr)
             }
 
-            if let Some(m) = reg_id.find(&self.data.as_str()[self.ptr..]).unwrap() {
-                token_list.push(Token{ttype: TokenType::Id(m.as_str().to_string()), pos: self.ptr});
-                self.ptr += m.as_str().len();
+            match self.peek(0) {
+                b'"' => {self.ptr += 1; return Ok(bytes)}
+                b'\\' => {
+                    //a bad escape is reported but the rest of the string is still read
+                    let start = self.ptr;
+                    match self.lex_escape() {
+                        Ok(c) => bytes.push(c),
+                        Err(kind) => self.error(start, kind)
+                    }
+                }
+                c => {bytes.push(c); self.ptr += 1}
+            }
+        }
+    }
+
+    //ptr is on the backslash, leaves ptr after the escape
+    fn lex_escape(&mut self) -> Result<u8, LexErrorKind> {
+        let escaped = self.peek(1);
+        self.ptr += 1;
+        if escaped.is_ascii() && escaped != 0 {
+            self.ptr += 1;
+        }
+
+        let c = match escaped {
+            b'n' => b'\n',
+            b't' => b'\t',
+            b'r' => b'\r',
+            b'0' => 0,
+            b'\\' => b'\\',
+            b'\'' => b'\'',
+            b'"' => b'"',
+            b'x' => {
+                let hex = match self.data.get(self.ptr..self.ptr + 2) {
+                    Some(h) if h.bytes().all(|b| b.is_ascii_hexdigit()) => h,
+                    _ => return Err(LexErrorKind::InvalidEscape)
+                };
+
+                let c = u8::from_str_radix(hex, 16).unwrap();
+                self.ptr += 2;
+                c
+            }
+            _ => return Err(LexErrorKind::InvalidEscape)
+        };
+
+        Ok(c)
+    }
+
+    //skips whitespace and comments until the next token, doc comments are tokens
+    //an unterminated block comment is reported at its opening position
+    fn skip_trivia(&mut self) {
+        loop {
+            self.skip_whitespace();
+
+            if self.at_doc_comment() {
+                return
+            }
+
+            if self.peek(0) == b'/' && self.peek(1) == b'/' {
+                self.skip_line_comment();
                 continue;
             }
 
-            if let Some(m) = reg_lit.find(&self.data.as_str()[self.ptr..]).unwrap() {
-                token_list.push(Token{ttype: TokenType::Lit(m.as_str().to_string()), pos: self.ptr});
-                self.ptr += m.as_str().len();
+            if self.peek(0) == b'/' && self.peek(1) == b'*' {
+                self.skip_block_comment();
                 continue;
             }
 
+            return
+        }
+    }
+
+    fn skip_line_comment(&mut self) {
+        while self.peek(0) != b'\n' && !self.at_end() {
+            self.ptr += 1;
+        }
+    }
+
+    //block comments nest, so every /* needs its own */
+    fn skip_block_comment(&mut self) {
+        let start = self.ptr;
+        let mut depth = 0;
 
-            match self.data.as_bytes()[self.ptr] {
-                b'(' => {token_list.push(Token {ttype: TokenType::ParenOpen, pos: self.ptr}); self.ptr += 1; continue},
-                b')' => {token_list.push(Token {ttype: TokenType::ParenClose, pos: self.ptr}); self.ptr += 1; continue},
-                b'[' => {token_list.push(Token {ttype: TokenType::SquareOpen, pos: self.ptr}); self.ptr += 1; continue},
-                b']' => {token_list.push(Token {ttype: TokenType::SquareClose, pos: self.ptr}); self.ptr += 1; continue},
-                b'{' => {token_list.push(Token {ttype: TokenType::CurlyOpen, pos: self.ptr}); self.ptr += 1; continue},
-                b'}' => {token_list.push(Token {ttype: TokenType::CurlyClose, pos: self.ptr}); self.ptr += 1; continue},
-                b';' => {token_list.push(Token {ttype: TokenType::SemiCol, pos: self.ptr}); self.ptr += 1; continue},
-                b':' => {token_list.push(Token {ttype: TokenType::Col, pos: self.ptr}); self.ptr += 1; continue},
-                b',' => {token_list.push(Token {ttype: TokenType::Comma, pos: self.ptr}); self.ptr += 1; continue},
-                b'.' => {token_list.push(Token {ttype: TokenType::Period, pos: self.ptr}); self.ptr += 1; continue},
-                _ => {}
+        loop {
+            if self.at_end() {
+                self.errors.push(LexError {
+                    span: Span::new(self.file, start, start + 2),
+                    kind: LexErrorKind::UnterminatedComment });
+                return
             }
 
-            return Err(self.ptr);
-        }
+            if self.peek(0) == b'/' && self.peek(1) == b'*' {
+                depth += 1;
+                self.ptr += 2;
+            } else if self.peek(0) == b'*' && self.peek(1) == b'/' {
+                depth -= 1;
+                self.ptr += 2;
 
-        token_list.push(Token {ttype: TokenType::EOF, pos: self.ptr});
-        return Ok(token_list);
+                if depth == 0 {
+                    return
+                }
+            } else {
+                self.ptr += 1;
+            }
+        }
     }
 
     fn skip_whitespace(&mut self) {
-        while (self.data.as_bytes()[self.ptr] == b' ' || self.data.as_bytes()[self.ptr] == b'\n' || self.data.as_bytes()[self.ptr] == b'\t') && self.data.as_bytes()[self.ptr] != 0 {
+        while matches!(self.peek(0), b' ' | b'\n' | b'\t' | b'\r') {
             self.ptr += 1;
         }
     }
 }
-
-
@@ -1,17 +1,23 @@
 use crate::definitions::*;
 use crate::statement::*;
 
+static DECLR_KEYWORDS: [&str; 9] = ["let", "const", "static", "type", "fn", "extern", "export", "struct", "enum"];
+
 #[derive(Debug)]
 pub struct Parser{
     pub tokens: Vec<Token>,
     pub ptr: usize,
+    pub module_doc: Option<String>, //from //! comments at the top of the file
+    pub errors: Vec<ParseError>,
 }
 
 impl Parser {
     pub fn new(tokens:  Vec<Token>) -> Self {
-        return Parser {
-            tokens: tokens,
-            ptr: 0
+        Parser {
+            tokens,
+            ptr: 0,
+            module_doc: None,
+            errors: vec![],
         }
     }
 
@@ -20,7 +26,7 @@ impl Parser {
         
         let temp = self.tokens[peek_location].clone();
         //println!("{:?}", temp);
-        return temp
+        temp
     }
 
     pub fn peek_forward(&self, n: i64) -> Option<Token> {
@@ -32,7 +38,32 @@ impl Parser {
 
         let temp = self.tokens[peek_location].clone();
         //println!("{:?}", temp);
-        return Some(temp)
+        Some(temp)
+    }
+
+    //error at the current token
+    pub fn error(&self, msg: &'static str, expected: Vec<TokenType>) -> ParseError {
+        let found = self.peek(0);
+
+        ParseError {
+            msg,
+            span: found.span,
+            found: found.ttype,
+            expected,
+            hint: None }
+    }
+
+    //error right after the previous token, for something missing at the end of a line
+    pub fn error_after(&self, msg: &'static str, expected: Vec<TokenType>) -> ParseError {
+        let mut e = self.error(msg, expected);
+
+        if let Some(prev) = self.peek_forward(-1) {
+            if self.ptr > 0 {
+                e.span = Span::new(prev.span.file, prev.span.end, prev.span.end);
+            }
+        }
+
+        e
     }
 
     pub fn advance(&mut self) {
@@ -41,16 +72,104 @@ impl Parser {
     }
 
 
-    pub fn parse(&mut self) -> Result<Vec<Statement>, &'static str> {
+    //joins consecutive /// comments, or //! comments if inner, one line each
+    pub fn parse_docs(&mut self, inner: bool) -> Option<String> {
+        let mut lines: Vec<String> = vec![];
+
+        loop {
+            match self.peek(0).ttype {
+                TokenType::Doc(d) if !inner => lines.push(d),
+                TokenType::InnerDoc(d) if inner => lines.push(d),
+                _ => break
+            }
+            self.advance();
+        }
+
+        if lines.is_empty() {
+            return None
+        }
+        Some(lines.join("\n"))
+    }
+
+    //never fails, syntax errors are collected in self.errors and left as Statement::Error
+    pub fn parse(&mut self) -> Vec<Statement> {
         let mut program: Vec<Statement> = vec![];
         
+        self.module_doc = self.parse_docs(true);
         //self.make_ptr_types();
 
         while self.peek(0).ttype != TokenType::EOF {
-            program.push( new_statement("Base").parse(self)?);
+            let start = self.peek(0);
+            let stmt = new_statement("Base").parse(self).expect("Base statements recover from errors");
+
+            //code only runs inside functions, globals are statics
+            if !stmt.is_item() {
+                self.report(ParseError {
+                    msg: "Expected Item at Top Level",
+                    span: start.span,
+                    found: start.ttype.clone(),
+                    expected: ["fn", "extern", "export", "struct", "enum", "type", "const", "static"].map(|k| TokenType::Key(k.to_string())).to_vec(),
+                    hint: Some("statements must be inside a function, global variables are declared with static") });
+
+                program.push(Statement::Error(start.span));
+                continue;
+            }
+
+            program.push(stmt);
+        }
+
+        program
+    }
+
+    //records the error, unless the lexer already reported the token
+    pub fn report(&mut self, e: ParseError) {
+        if e.found != TokenType::Error {
+            self.errors.push(e);
+        }
+    }
+
+    //panic mode recovery: skips ahead to the end of the broken statement,
+    //which is after a ; or before a } or declaration keyword, ignoring nested blocks
+    pub fn synchronize(&mut self, start: usize) {
+        //blocks the broken statement already opened
+        let mut depth: i32 = 0;
+        for t in &self.tokens[start..self.ptr] {
+            match t.ttype {
+                TokenType::CurlyOpen => depth += 1,
+                TokenType::CurlyClose => depth = (depth - 1).max(0),
+                _ => {}
+            }
         }
 
-        Ok(program)
+        loop {
+            match self.peek(0).ttype {
+                TokenType::EOF => return,
+
+                TokenType::CurlyOpen => depth += 1,
+
+                TokenType::CurlyClose => {
+                    if depth == 0 {
+                        //a stray } would otherwise never be consumed
+                        if self.ptr == start {
+                            self.advance();
+                        }
+                        return
+                    }
+                    depth -= 1;
+                }
+
+                TokenType::SemiCol if depth == 0 => {
+                    self.advance();
+                    return
+                }
+
+                TokenType::Key(k) if depth == 0 && self.ptr != start && DECLR_KEYWORDS.contains(&k.as_str()) => return,
+
+                _ => {}
+            }
+
+            self.advance();
+        }
     }
 
     /*fn make_ptr_types(&mut self) {
@@ -59,7 +178,7 @@ impl Parser {
                 if matches!(self.peek(1).ttype, TokenType::Id(_)) {
                     self.tokens[self.ptr] = Token {
                         ttype: TokenType::Id("ptr@".to_string() + &self.peek(1).data()),
-                        pos: self.peek(0).pos
+                        span: self.peek(0).span
                     };
 
                     self.tokens.remove(self.ptr + 1);
@@ -71,4 +190,37 @@ impl Parser {
         println!("{:#?}", self.tokens);
         self.ptr = 0;
     }*/
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub msg: &'static str,
+    pub span: Span,
+    pub found: TokenType, //only the kind of token, span says where it is
+    pub expected: Vec<TokenType>, //Id and Lit stand for any identifier or literal
+    pub hint: Option<&'static str>,
+}
+
+impl ParseError {
+    pub fn hint(mut self, hint: &'static str) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, found {}", self.msg, self.found.describe())?;
+
+        if !self.expected.is_empty() {
+            let expected: Vec<String> = self.expected.iter().map(|t| t.describe()).collect();
+            write!(f, " (expected {})", expected.join(" or "))?;
+        }
+
+        if let Some(hint) = self.hint {
+            write!(f, "\n    hint: {}", hint)?;
+        }
+
+        Ok(())
+    }
+}
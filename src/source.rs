@@ -0,0 +1,80 @@
+use crate::definitions::*;
+
+//every file handed to the compiler, so that spans can be turned into lines and columns
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+#[derive(Debug)]
+pub struct SourceFile {
+    pub name: String,
+    pub text: String,
+    //byte offset at which each line begins, always starts with 0
+    line_starts: Vec<usize>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap { files: vec![] }
+    }
+
+    pub fn add_file(&mut self, name: String, text: String) -> FileId {
+        let mut line_starts = vec![0];
+        for (n, c) in text.bytes().enumerate() {
+            if c == b'\n' {
+                line_starts.push(n + 1);
+            }
+        }
+
+        self.files.push(SourceFile { name, text, line_starts });
+        self.files.len() - 1
+    }
+
+    pub fn file(&self, id: FileId) -> &SourceFile {
+        &self.files[id]
+    }
+
+    //(Line, Col), both starting at 1
+    pub fn line_col(&self, file: FileId, pos: usize) -> (usize, usize) {
+        self.files[file].line_col(pos)
+    }
+
+    pub fn span_start(&self, span: Span) -> (usize, usize) {
+        self.line_col(span.file, span.start)
+    }
+}
+
+impl SourceFile {
+    pub fn line_col(&self, pos: usize) -> (usize, usize) {
+        let pos = pos.min(self.text.len());
+        let line = self.line_starts.partition_point(|&start| start <= pos) - 1;
+
+        let line_start = self.line_starts[line];
+        let col = match self.text.get(line_start..pos) {
+            Some(s) => s.chars().count(),
+            None => pos - line_start
+        };
+
+        (line + 1, col + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_to_files_and_lines() {
+        let mut map = SourceMap::new();
+        let a = map.add_file("a.hla".to_string(), "fn\nmain".to_string());
+        let b = map.add_file("b.hla".to_string(), "x\n\ny é z".to_string());
+
+        assert_eq!(map.file(a).name, "a.hla");
+        assert_eq!(map.file(b).name, "b.hla");
+        assert_eq!(map.span_start(Span::new(a, 3, 7)), (2, 1));
+        assert_eq!(map.line_col(b, 2), (2, 1));
+        //columns count characters, not bytes
+        assert_eq!(map.line_col(b, 8), (3, 5));
+    }
+}
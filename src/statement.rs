@@ -10,23 +10,32 @@ pub enum Statement {
     StructDeclr(Box<StructDeclr>),
     EnumDeclr(Box<EnumDeclr>),
     Parameters(Vec<(Token, DeclrType)>), //(name, type)
-    Variant(Vec<Token>),
+    Variant(Vec<VariantDeclr>),
     VarDeclr(VarDeclr),
+    ConstDeclr(VarDeclr), //value is always Some
+    StaticDeclr(VarDeclr, bool), //(declaration, mutable)
+    TypeAlias(Token, DeclrType, Option<String>), //(name, aliased type, doc)
     Stmt,
-    LoopStmt(Box<Statement>), //must contain Statement::Block
+    LoopStmt(Box<Statement>, Option<Token>), //(Statement::Block, label)
     IfStmt(Box<CondStmt>),
-    WhileStmt(Box<CondStmt>),
-    BreakStmt(Token),
+    WhileStmt(Box<CondStmt>, Option<Token>), //(condition and body, label)
+    ForStmt(Box<ForStmt>),
+    MatchStmt(Box<MatchStmt>),
+    AsmStmt(Box<AsmStmt>),
+    BreakStmt(Token, Option<Token>),    //(keyword, label)
+    ContinueStmt(Token, Option<Token>), //(keyword, label)
     ReturnStmt(Token, Expr),
     ExprStmt(Expr),
     Block(Vec<Statement>),
+    Error(Span), //a statement that failed to parse, the error is in Parser::errors
 }
 
 #[derive(Clone, Debug)]
 pub struct VarDeclr {
     pub name: Token,
     pub var_type: DeclrType,
-    pub value: Option<Expr>
+    pub value: Option<Expr>,
+    pub doc: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -35,20 +44,138 @@ pub struct FnDeclr {
     pub params: Statement, //Statement::parameters
     pub ret_type: DeclrType,
     pub body: Statement,
+    pub doc: Option<String>,
+    pub linkage: Linkage,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Linkage {
+    Local,
+    Extern, //written in assembly, there is no body
+    Export, //callable from assembly by its name
 }
 
 #[derive(Clone, Debug)]
 pub struct StructDeclr {
     pub name: Token,
     pub params: Statement, //Statement::parameters
+    pub field_docs: Vec<Option<String>>, //one per field in params
+    pub doc: Option<String>,
 }
 
 #[derive(Clone, Debug)]
 pub struct EnumDeclr {
     pub name: Token,
+    pub base_type: Option<DeclrType>, //enum Name: u16 {}, picked from the discriminants if left out
     pub variants: Statement, //Statement::variant
+    pub doc: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct VariantDeclr {
+    pub name: Token,
+    pub value: Option<Expr>, //explicit discriminant, otherwise one more than the previous variant
+    pub doc: Option<String>,
+}
+
+//for var: var_type in start..end step step {body}
+#[derive(Clone, Debug)]
+pub struct ForStmt {
+    pub var: Token,
+    pub var_type: DeclrType,
+    pub start: Expr,
+    pub end: Expr,
+    pub inclusive: bool, //..= instead of ..
+    pub step: Option<Expr>,
+    pub body: Statement, //Statement::Block
+    pub label: Option<Token>,
+}
+
+//match value { Enum::A => {}, Enum::B | Enum::C => {}, _ => {} }
+#[derive(Clone, Debug)]
+pub struct MatchStmt {
+    pub keyword: Token,
+    pub value: Expr,
+    pub arms: Vec<MatchArm>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchArm {
+    pub patterns: Vec<MatchPattern>, //separated by |
+    pub body: Statement,             //Statement::Block
+}
+
+#[derive(Clone, Debug)]
+pub enum MatchPattern {
+    Variant(Token, Token), //(enum, variant)
+    Wildcard(Token),
+}
+
+//asm { "out {port}, {value}"; in port = p; in value = v; out status = s; clobber a, flags; }
+#[derive(Clone, Debug)]
+pub struct AsmStmt {
+    pub lines: Vec<Token>, //string literals, {name} stands for an operand and {{ }} for braces
+    pub operands: Vec<AsmOperand>,
+    pub clobbers: Vec<Token>,
 }
 
+#[derive(Clone, Debug)]
+pub struct AsmOperand {
+    pub kind: AsmOperandKind,
+    pub name: Token,
+    pub value: Expr, //outputs have to be places
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AsmOperandKind {
+    In,
+    Out,
+    InOut,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AsmPiece {
+    Text(String),
+    Operand(String),
+}
+
+//splits an assembly line around its operands, None if a brace is unmatched
+pub fn asm_template(line: &str) -> Option<Vec<AsmPiece>> {
+    let mut pieces: Vec<AsmPiece> = vec![];
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {chars.next(); text.push('{')}
+            '}' if chars.peek() == Some(&'}') => {chars.next(); text.push('}')}
+            '}' => return None,
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return None
+                    }
+                }
+
+                if !text.is_empty() {
+                    pieces.push(AsmPiece::Text(std::mem::take(&mut text)));
+                }
+                pieces.push(AsmPiece::Operand(name.trim().to_string()));
+            }
+            c => text.push(c)
+        }
+    }
+
+    if !text.is_empty() {
+        pieces.push(AsmPiece::Text(text));
+    }
+    Some(pieces)
+}
+
+//else if chains are nested, the false branch is then another Statement::IfStmt
 #[derive(Clone, Debug)]
 pub struct CondStmt {
     pub cond: Expr,
@@ -65,19 +192,25 @@ pub fn new_statement(t: &'static str) -> Statement{
                 name: BLANK_TOKEN,
                 params: new_statement("Base"),
                 ret_type: DeclrType::BasicType(BLANK_TOKEN),
-                body: new_statement("Base") }))
+                body: new_statement("Base"),
+                doc: None,
+                linkage: Linkage::Local }))
         }
 
         "StructDeclr" => {
             Statement::StructDeclr(Box::new(StructDeclr {
                 name: BLANK_TOKEN,
-                params: new_statement("Base") }))
+                params: new_statement("Base"),
+                field_docs: vec![],
+                doc: None }))
         }
 
         "EnumDeclr" => {
             Statement::EnumDeclr(Box::new(EnumDeclr {
                 name: BLANK_TOKEN,
-                variants: new_statement("Base") }))
+                base_type: None,
+                variants: new_statement("Base"),
+                doc: None }))
         }
 
         "Params" => Statement::Parameters(vec![]),
@@ -88,8 +221,20 @@ pub fn new_statement(t: &'static str) -> Statement{
             Statement::VarDeclr(VarDeclr {
                 name: BLANK_TOKEN,
                 var_type: DeclrType::BasicType(BLANK_TOKEN),
-                value: None })
+                value: None,
+                doc: None })
         },
+        "ConstDeclr" => Statement::ConstDeclr(VarDeclr {
+            name: BLANK_TOKEN,
+            var_type: DeclrType::BasicType(BLANK_TOKEN),
+            value: None,
+            doc: None }),
+        "StaticDeclr" => Statement::StaticDeclr(VarDeclr {
+            name: BLANK_TOKEN,
+            var_type: DeclrType::BasicType(BLANK_TOKEN),
+            value: None,
+            doc: None }, false),
+        "TypeAlias" => Statement::TypeAlias(BLANK_TOKEN, DeclrType::BasicType(BLANK_TOKEN), None),
         "Stmt" => Statement::Stmt,
         
         "ExprStmt" => {
@@ -97,7 +242,7 @@ pub fn new_statement(t: &'static str) -> Statement{
         },
         "Block"=> Statement::Block(vec![]),
         
-        "LoopStmt" => Statement::LoopStmt(Box::new(new_statement("Block"))),
+        "LoopStmt" => Statement::LoopStmt(Box::new(new_statement("Block")), None),
         
         "IfStmt" => Statement::IfStmt(Box::new(CondStmt {
             cond: new_expr("Base"),
@@ -108,9 +253,31 @@ pub fn new_statement(t: &'static str) -> Statement{
         "WhileStmt" => Statement::WhileStmt(Box::new(CondStmt {
             cond: new_expr("Base"),
             true_branch: new_statement("Block"),
-            false_branch: None })),
+            false_branch: None }), None),
+        
+        "ForStmt" => Statement::ForStmt(Box::new(ForStmt {
+            var: BLANK_TOKEN,
+            var_type: DeclrType::BasicType(BLANK_TOKEN),
+            start: new_expr("Base"),
+            end: new_expr("Base"),
+            inclusive: false,
+            step: None,
+            body: new_statement("Block"),
+            label: None })),
         
-        "BreakStmt" => {Statement::BreakStmt(BLANK_TOKEN,)},
+        "MatchStmt" => Statement::MatchStmt(Box::new(MatchStmt {
+            keyword: BLANK_TOKEN,
+            value: new_expr("Base"),
+            arms: vec![] })),
+
+        "AsmStmt" => Statement::AsmStmt(Box::new(AsmStmt {
+            lines: vec![],
+            operands: vec![],
+            clobbers: vec![] })),
+
+        "BreakStmt" => {Statement::BreakStmt(BLANK_TOKEN, None)},
+
+        "ContinueStmt" => {Statement::ContinueStmt(BLANK_TOKEN, None)},
         
         "ReturnStmt" => {Statement::ReturnStmt(BLANK_TOKEN, new_expr("Base"))}
         _ => panic!("Need to implement new_statement for {}", t)
@@ -118,43 +285,104 @@ pub fn new_statement(t: &'static str) -> Statement{
 }
 
 impl Statement {
-    pub fn parse(&mut self, p: &mut Parser) -> Result<Statement, &'static str> {
+    pub fn parse(&mut self, p: &mut Parser) -> Result<Statement, ParseError> {
         *self = match self {
-            Statement::Base => new_statement("Declr").parse(p)?,
+            Statement::Base => {
+                let start = p.ptr;
+
+                match new_statement("Declr").parse(p) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        let span = e.span;
+                        p.report(e);
+                        p.synchronize(start);
+                        Statement::Error(span)
+                    }
+                }
+            }
 
             Statement::Declr => 'b: {
+                if matches!(p.peek(0).ttype, TokenType::InnerDoc(_)) {
+                    return Err(p.error("Inner Doc Comment must come first in its Item", vec![]).hint("use /// to document the item that follows"))
+                }
+
+                //every declaration keeps its docs, there is nothing to document on other statements
+                let doc = p.parse_docs(false);
+                let documented = match p.peek(0).ttype {
+                    TokenType::Key(k) => ["let", "const", "static", "type", "fn", "extern", "export", "struct", "enum"].contains(&k.as_str()),
+                    _ => false
+                };
+
+                if doc.is_some() && !documented {
+                    return Err(p.error("Expected Declaration after Doc Comment", vec![TokenType::Key("let".to_string()), TokenType::Key("fn".to_string()), TokenType::Key("struct".to_string())]).hint("doc comments document the declaration after them, use // for other comments"))
+                }
+
                 if p.peek(0).ttype == TokenType::Key("let".to_string())  {
                     p.advance();
-                    break 'b new_statement("VarDeclr").parse(p)?
+                    break 'b new_statement("VarDeclr").parse(p)?.with_doc(doc)
+                }
+
+                if p.peek(0).ttype == TokenType::Key("const".to_string())  {
+                    p.advance();
+                    break 'b new_statement("ConstDeclr").parse(p)?.with_doc(doc)
+                }
+
+                if p.peek(0).ttype == TokenType::Key("static".to_string())  {
+                    p.advance();
+                    break 'b new_statement("StaticDeclr").parse(p)?.with_doc(doc)
+                }
+
+                if p.peek(0).ttype == TokenType::Key("type".to_string())  {
+                    p.advance();
+                    break 'b new_statement("TypeAlias").parse(p)?.with_doc(doc)
                 }
 
                 if p.peek(0).ttype == TokenType::Key("fn".to_string()) {
                     p.advance();
-                    break 'b new_statement("FnDeclr").parse(p)?
+                    break 'b new_statement("FnDeclr").parse(p)?.with_doc(doc)
+                }
+
+                let linkage = match p.peek(0).ttype {
+                    TokenType::Key(k) if k == "extern" => Some(Linkage::Extern),
+                    TokenType::Key(k) if k == "export" => Some(Linkage::Export),
+                    _ => None
+                };
+
+                if let Some(linkage) = linkage {
+                    p.advance();
+
+                    if p.peek(0).ttype != TokenType::Key("fn".to_string()) {
+                        return Err(p.error("Expected fn after Linkage", vec![TokenType::Key("fn".to_string())]).hint("only functions can be extern or export"))
+                    }
+                    p.advance();
+
+                    break 'b new_statement("FnDeclr").with_linkage(linkage).parse(p)?.with_doc(doc)
                 }
 
                 if p.peek(0).ttype == TokenType::Key("struct".to_string()) {
                     p.advance();
-                    break 'b new_statement("StructDeclr").parse(p)?
+                    break 'b new_statement("StructDeclr").parse(p)?.with_doc(doc)
                 }
 
                 if p.peek(0).ttype == TokenType::Key("enum".to_string()) {
                     p.advance();
-                    break 'b new_statement("EnumDeclr").parse(p)?
+                    break 'b new_statement("EnumDeclr").parse(p)?.with_doc(doc)
                 }
 
                 new_statement("Stmt").parse(p)?
             }
 
-            Statement::FnDeclr(_) => {
+            Statement::FnDeclr(template) => {
+                let linkage = template.linkage.clone();
+
                 if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                    return Err("Expected Identifier for Function Name")
+                    return Err(p.error("Expected Identifier for Function Name", vec![TokenType::Id(String::new())]))
                 }
                 let fn_name = p.peek(0);
                 p.advance();
 
                 if !matches!(p.peek(0).ttype, TokenType::ParenOpen) {
-                    return Err("Expected Opening Parentheses after Function Name")
+                    return Err(p.error("Expected Opening Parentheses after Function Name", vec![TokenType::ParenOpen]))
                 }
                 p.advance();
 
@@ -165,108 +393,170 @@ impl Statement {
                 }
 
                 if p.peek(0).ttype != TokenType::ParenClose {
-                    return Err("Expected Closing Parentheses after Function Declaration")
+                    return Err(p.error("Expected Closing Parentheses after Function Declaration", vec![TokenType::ParenClose]))
                 }
                 p.advance();
 
                 if p.peek(0).ttype != TokenType::Arrow {
-                    return Err("Expected Arrow to Denote Function Type")
+                    return Err(p.error("Expected Arrow to Denote Function Type", vec![TokenType::Arrow]).hint("functions without a value return void: fn name() -> void"))
                 }
                 p.advance();
 
                 let ret_type = parse_type(p)?;
 
+                //the body of an extern function is somewhere in assembly
+                if linkage == Linkage::Extern {
+                    if p.peek(0).ttype != TokenType::SemiCol {
+                        return Err(p.error_after("Expected Semicolon after Extern Function", vec![TokenType::SemiCol]).hint("extern functions are declared without a body"))
+                    }
+                    p.advance();
+
+                    return Ok(Statement::FnDeclr(Box::new(FnDeclr {
+                        name: fn_name,
+                        params,
+                        ret_type,
+                        body: new_statement("Block"),
+                        doc: None,
+                        linkage })))
+                }
+
                 if p.peek(0).ttype != TokenType::CurlyOpen {
-                    return Err("Expected Opening Curly Brace for Function Body")
+                    return Err(p.error("Expected Opening Curly Brace for Function Body", vec![TokenType::CurlyOpen]))
                 }
                 p.advance();
 
+                let doc = p.parse_docs(true);
                 let body = new_statement("Block").parse(p)?;
 
                 Statement::FnDeclr(Box::new(FnDeclr {
                     name: fn_name,
-                    params: params,
-                    ret_type: ret_type,
-                    body: body }
+                    params,
+                    ret_type,
+                    body,
+                    doc,
+                    linkage }
                 ))
             }
 
             Statement::StructDeclr(_) => {
                 if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                    return Err("Expected Identifier for Struct Name")
+                    return Err(p.error("Expected Identifier for Struct Name", vec![TokenType::Id(String::new())]))
                 }
                 let name = p.peek(0);
                 p.advance();
 
                 if !matches!(p.peek(0).ttype, TokenType::CurlyOpen) {
-                    return Err("Expected Curly Bracket after Struct Name")
+                    return Err(p.error("Expected Curly Bracket after Struct Name", vec![TokenType::CurlyOpen]))
                 }
                 p.advance();
 
+                let doc = p.parse_docs(true);
 
-                let mut params = new_statement("Params");
-                if !matches!(p.peek(0).ttype, TokenType::CurlyClose) {
-                    params.parse(p)?;
+                //same as Params, but every field can have its own doc comment
+                let mut fields: Vec<(Token, DeclrType)> = vec![];
+                let mut field_docs: Vec<Option<String>> = vec![];
+
+                while !matches!(p.peek(0).ttype, TokenType::CurlyClose) {
+                    field_docs.push(p.parse_docs(false));
+
+                    if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
+                        return Err(p.error("Expected Identifier for Field Name", vec![TokenType::Id(String::new())]))
+                    }
+                    let field_name = p.peek(0);
+                    p.advance();
+
+                    if !matches!(p.peek(0).ttype, TokenType::Col) {
+                        return Err(p.error("Expected Colon after Field Name", vec![TokenType::Col]))
+                    }
+                    p.advance();
+
+                    fields.push((field_name, parse_type(p)?));
+
+                    if p.peek(0).ttype != TokenType::Comma {
+                        break
+                    }
+                    p.advance();
                 }
 
                 if !matches!(p.peek(0).ttype, TokenType::CurlyClose) {
-                    return Err("Expected Closing Curly Bracket after Struct Declaration")
+                    return Err(p.error("Expected Closing Curly Bracket after Struct Declaration", vec![TokenType::Comma, TokenType::CurlyClose]))
                 }
                 p.advance();
 
                 Statement::StructDeclr(Box::new(StructDeclr {
-                    name: name,
-                    params: params }))
+                    name,
+                    params: Statement::Parameters(fields),
+                    field_docs,
+                    doc }))
             }
 
             Statement::EnumDeclr(_) => {
                 if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                    return Err("Expected Identifier for Enum Name")
+                    return Err(p.error("Expected Identifier for Enum Name", vec![TokenType::Id(String::new())]))
                 }
                 let name = p.peek(0);
                 p.advance();
 
+                let mut base_type = None;
+                if p.peek(0).ttype == TokenType::Col {
+                    p.advance();
+                    base_type = Some(parse_type(p)?);
+                }
+
                 if !matches!(p.peek(0).ttype, TokenType::CurlyOpen) {
-                    return Err("Expected Curly Bracket after Enum Name")
+                    return Err(p.error("Expected Curly Bracket after Enum Name", vec![TokenType::CurlyOpen, TokenType::Col]))
                 }
                 p.advance();
 
+                let doc = p.parse_docs(true);
                 let mut variants = new_statement("Variant");
                 if !matches!(p.peek(0).ttype, TokenType::CurlyClose) {
                     variants.parse(p)?;
                 }
 
                 if !matches!(p.peek(0).ttype, TokenType::CurlyClose) {
-                    return Err("Expected Closing Curly Bracket after Enum Declaration")
+                    return Err(p.error("Expected Closing Curly Bracket after Enum Declaration", vec![TokenType::Comma, TokenType::CurlyClose]))
                 }
                 p.advance();
 
                 Statement::EnumDeclr(Box::new(EnumDeclr {
-                    name: name,
-                    variants: variants }))
+                    name,
+                    base_type,
+                    variants,
+                    doc }))
             }
 
             Statement::Variant(_) => {
-                let mut variant_vec: Vec<Token> = vec![];
+                let mut variant_vec: Vec<VariantDeclr> = vec![];
 
-                if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                    return Err("Expected Identifier for Variant Name")
-                }
-                let param_name = p.peek(0);
-                p.advance();
+                loop {
+                    let doc = p.parse_docs(false);
 
-                variant_vec.push(param_name);
-                
-                while p.peek(0).ttype == TokenType::Comma {
-                    p.advance();
-                    
                     if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                        return Err("Expected Identifier for Variant Name")
+                        return Err(p.error("Expected Identifier for Variant Name", vec![TokenType::Id(String::new())]))
                     }
-                    let param_name = p.peek(0);
+                    let variant_name = p.peek(0);
                     p.advance();
-    
-                    variant_vec.push(param_name);
+
+                    let mut value = None;
+                    if p.peek(0).ttype == TokenType::Op("=".to_string()) {
+                        p.advance();
+                        value = Some(new_expr("Base").parse(p)?);
+                    }
+
+                    variant_vec.push(VariantDeclr {
+                        name: variant_name,
+                        value,
+                        doc });
+
+                    if p.peek(0).ttype != TokenType::Comma {
+                        break
+                    }
+                    p.advance();
+
+                    if p.peek(0).ttype == TokenType::CurlyClose {
+                        break
+                    }
                 }
                 Statement::Variant(variant_vec)
             }
@@ -275,13 +565,13 @@ impl Statement {
                 let mut param_vec: Vec<(Token, DeclrType)> = vec![];
 
                 if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                    return Err("Expected Identifier for Parameter Name")
+                    return Err(p.error("Expected Identifier for Parameter Name", vec![TokenType::Id(String::new())]))
                 }
                 let param_name = p.peek(0);
                 p.advance();
 
                 if !matches!(p.peek(0).ttype, TokenType::Col) {
-                    return Err("Expected Colon after Parameter Name")
+                    return Err(p.error("Expected Colon after Parameter Name", vec![TokenType::Col]))
                 }
                 p.advance();
 
@@ -293,13 +583,13 @@ impl Statement {
                     p.advance();
 
                     if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                        return Err("Expected Identifier for Parameter Name")
+                        return Err(p.error("Expected Identifier for Parameter Name", vec![TokenType::Id(String::new())]))
                     }
                     let param_name = p.peek(0);
                     p.advance();
     
                     if !matches!(p.peek(0).ttype, TokenType::Col) {
-                        return Err("Expected Colon after Parameter Name")
+                        return Err(p.error("Expected Colon after Parameter Name", vec![TokenType::Col]))
                     }
                     p.advance();
     
@@ -312,14 +602,14 @@ impl Statement {
 
             Statement::VarDeclr(_) => {
                 if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
-                    return Err("Expected Identifier for Variable Name")
+                    return Err(p.error("Expected Identifier for Variable Name", vec![TokenType::Id(String::new())]))
                 }
 
                 let name = p.peek(0);
                 p.advance();
 
                 if !matches!(p.peek(0).ttype, TokenType::Col) {
-                    return Err("Expected Colon After Variable Name")
+                    return Err(p.error("Expected Colon After Variable Name", vec![TokenType::Col]).hint("variables need a type: let name: type"))
                 }
                 p.advance();
 
@@ -333,23 +623,111 @@ impl Statement {
                 }
 
                 if !matches!(p.peek(0).ttype, TokenType::SemiCol) {
-                    return Err("Expected Semicolon after Declaration")
+                    return Err(p.error_after("Expected Semicolon after Declaration", vec![TokenType::SemiCol]))
                 }
                 
                 p.advance();
 
                 Statement::VarDeclr(VarDeclr {
-                    name: name,
+                    name,
                     var_type: vtype,
-                    value: value })
+                    value,
+                    doc: None })
                 }
 
+            Statement::ConstDeclr(_) => {
+                if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
+                    return Err(p.error("Expected Identifier for Constant Name", vec![TokenType::Id(String::new())]))
+                }
+
+                let name = p.peek(0);
+                p.advance();
+
+                if !matches!(p.peek(0).ttype, TokenType::Col) {
+                    return Err(p.error("Expected Colon After Constant Name", vec![TokenType::Col]).hint("constants need a type: const NAME: type = value;"))
+                }
+                p.advance();
+
+                let vtype = parse_type(p)?;
+
+                if p.peek(0).ttype != TokenType::Op("=".to_string()) {
+                    return Err(p.error("Expected Value for Constant", vec![TokenType::Op("=".to_string())]).hint("constants need a value: const NAME: type = value;"))
+                }
+                p.advance();
+
+                let value = new_expr("Base").parse(p)?;
+
+                if !matches!(p.peek(0).ttype, TokenType::SemiCol) {
+                    return Err(p.error_after("Expected Semicolon after Declaration", vec![TokenType::SemiCol]))
+                }
+                p.advance();
+
+                Statement::ConstDeclr(VarDeclr {
+                    name,
+                    var_type: vtype,
+                    value: Some(value),
+                    doc: None })
+            }
+
+            Statement::TypeAlias(_, _, _) => {
+                if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
+                    return Err(p.error("Expected Identifier for Type Name", vec![TokenType::Id(String::new())]))
+                }
+
+                let name = p.peek(0);
+                p.advance();
+
+                if p.peek(0).ttype != TokenType::Op("=".to_string()) {
+                    return Err(p.error("Expected = after Type Name", vec![TokenType::Op("=".to_string())]).hint("aliases are declared as: type Name = type;"))
+                }
+                p.advance();
+
+                let aliased = parse_type(p)?;
+
+                if !matches!(p.peek(0).ttype, TokenType::SemiCol) {
+                    return Err(p.error_after("Expected Semicolon after Declaration", vec![TokenType::SemiCol]))
+                }
+                p.advance();
+
+                Statement::TypeAlias(name, aliased, None)
+            }
+
+            //the rest of a static is written like a let
+            Statement::StaticDeclr(_, _) => {
+                let mutable = p.peek(0).ttype == TokenType::Key("mut".to_string());
+                if mutable {
+                    p.advance();
+                }
+
+                match new_statement("VarDeclr").parse(p)? {
+                    Statement::VarDeclr(declr) => Statement::StaticDeclr(declr, mutable),
+                    _ => panic!("VarDeclr parses into a VarDeclr")
+                }
+            }
+
             Statement::Stmt => 'b: {
                 if matches!(p.peek(0).ttype, TokenType::CurlyOpen) {
                     p.advance();
                     break 'b new_statement("Block").parse(p)?
                 }
 
+                if let TokenType::Label(_) = p.peek(0).ttype {
+                    let label = p.peek(0);
+                    p.advance();
+
+                    if p.peek(0).ttype != TokenType::Col {
+                        return Err(p.error("Expected Colon after Label", vec![TokenType::Col]))
+                    }
+                    p.advance();
+
+                    let loops = ["loop", "while", "for"].map(|k| TokenType::Key(k.to_string()));
+                    if !loops.contains(&p.peek(0).ttype) {
+                        return Err(p.error("Expected Loop after Label", loops.to_vec()).hint("only loop, while and for can be labeled"))
+                    }
+
+                    break 'b new_statement("Stmt").parse(p)?.with_label(label)
+                }
+
                 if p.peek(0).ttype == TokenType::Key("loop".to_string()) {
                     p.advance();
                     break 'b new_statement("LoopStmt").parse(p)?
@@ -365,15 +743,29 @@ impl Statement {
                     break 'b new_statement("WhileStmt").parse(p)?
                 }
 
+                if p.peek(0).ttype == TokenType::Key("for".to_string()) {
+                    p.advance();
+                    break 'b new_statement("ForStmt").parse(p)?
+                }
+
+                if p.peek(0).ttype == TokenType::Key("match".to_string()) {
+                    p.advance();
+                    break 'b new_statement("MatchStmt").parse(p)?
+                }
+
                 if p.peek(0).ttype == TokenType::Key("break".to_string()) {
                     p.advance();
+                    break 'b new_statement("BreakStmt").parse(p)?
+                }
 
-                    if p.peek(0).ttype == TokenType::CurlyClose {
-                        break 'b new_statement("BreakStmt")
-                    } else {
-                        return Err("Expected Closing Brace after Break")
-                    }
-                    
+                if p.peek(0).ttype == TokenType::Key("asm".to_string()) {
+                    p.advance();
+                    break 'b new_statement("AsmStmt").parse(p)?
+                }
+
+                if p.peek(0).ttype == TokenType::Key("continue".to_string()) {
+                    p.advance();
+                    break 'b new_statement("ContinueStmt").parse(p)?
                 }
 
                 if p.peek(0).ttype == TokenType::Key("return".to_string()) {
@@ -385,7 +777,7 @@ impl Statement {
                 //if this point is reached, statement is ExprStmt
                 let e = new_expr("Base").parse(p)?;
                 if !matches!(p.peek(0).ttype, TokenType::SemiCol) {
-                    return Err("Expected Semicolon after Expression Statement")
+                    return Err(p.error_after("Expected Semicolon after Expression Statement", vec![TokenType::SemiCol]))
                 }
                 
                 p.advance();
@@ -400,22 +792,167 @@ impl Statement {
                 Statement::ReturnStmt(r, value)
             }
 
-            Statement::LoopStmt(_) => {
+            Statement::MatchStmt(_) => {
+                let keyword = p.peek(-1);
+                let value = new_expr("Base").parse(p)?;
+
+                if p.peek(0).ttype != TokenType::CurlyOpen {
+                    return Err(p.error("Expected Curly Brace after Match Value", vec![TokenType::CurlyOpen]))
+                }
+                p.advance();
+
+                let mut arms: Vec<MatchArm> = vec![];
+
+                while p.peek(0).ttype != TokenType::CurlyClose {
+                    let mut patterns = vec![MatchPattern::parse(p)?];
+
+                    while p.peek(0).ttype == TokenType::Op("|".to_string()) {
+                        p.advance();
+                        patterns.push(MatchPattern::parse(p)?);
+                    }
+
+                    if p.peek(0).ttype != TokenType::FatArrow {
+                        return Err(p.error("Expected => after Match Pattern", vec![TokenType::FatArrow, TokenType::Op("|".to_string())]))
+                    }
+                    p.advance();
+
+                    if p.peek(0).ttype != TokenType::CurlyOpen {
+                        return Err(p.error("Expected Block for Match Arm", vec![TokenType::CurlyOpen]))
+                    }
+                    p.advance();
+
+                    let body = new_statement("Block").parse(p)?;
+                    arms.push(MatchArm {
+                        patterns,
+                        body });
+
+                    if p.peek(0).ttype == TokenType::Comma {
+                        p.advance();
+                    }
+                }
+                p.advance();
+
+                Statement::MatchStmt(Box::new(MatchStmt {
+                    keyword,
+                    value,
+                    arms }))
+            }
+
+            Statement::AsmStmt(_) => {
+                if p.peek(0).ttype != TokenType::CurlyOpen {
+                    return Err(p.error("Expected Curly Brace after asm", vec![TokenType::CurlyOpen]))
+                }
+                p.advance();
+
+                let mut lines: Vec<Token> = vec![];
+                let mut operands: Vec<AsmOperand> = vec![];
+                let mut clobbers: Vec<Token> = vec![];
+
+                while p.peek(0).ttype != TokenType::CurlyClose {
+                    let kind = match p.peek(0).ttype {
+                        TokenType::Key(k) if k == "in" => Some(AsmOperandKind::In),
+                        TokenType::Id(k) if k == "out" => Some(AsmOperandKind::Out),
+                        TokenType::Id(k) if k == "inout" => Some(AsmOperandKind::InOut),
+                        _ => None
+                    };
+
+                    if let TokenType::Str(_) = p.peek(0).ttype {
+                        lines.push(p.peek(0));
+                        p.advance();
+
+                    } else if let Some(kind) = kind {
+                        p.advance();
+
+                        if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
+                            return Err(p.error("Expected Operand Name", vec![TokenType::Id(String::new())]))
+                        }
+                        let name = p.peek(0);
+                        p.advance();
+
+                        if p.peek(0).ttype != TokenType::Op("=".to_string()) {
+                            return Err(p.error("Expected = after Operand Name", vec![TokenType::Op("=".to_string())]).hint("operands are bound as: in name = value;"))
+                        }
+                        p.advance();
+
+                        operands.push(AsmOperand {
+                            kind,
+                            name,
+                            value: new_expr("Base").parse(p)?
+                        });
+
+                    } else if p.peek(0).ttype == TokenType::Id("clobber".to_string()) {
+                        p.advance();
+
+                        loop {
+                            if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
+                                return Err(p.error("Expected Register Name", vec![TokenType::Id(String::new())]))
+                            }
+                            clobbers.push(p.peek(0));
+                            p.advance();
+
+                            if p.peek(0).ttype != TokenType::Comma {
+                                break
+                            }
+                            p.advance();
+                        }
+
+                    } else {
+                        return Err(p.error("Expected Assembly Line, Operand or Clobber List", vec![TokenType::Str(vec![]), TokenType::Key("in".to_string()), TokenType::Id("out".to_string()), TokenType::Id("clobber".to_string())]))
+                    }
+
+                    //the semicolon can be left out right before the closing brace
+                    match p.peek(0).ttype {
+                        TokenType::SemiCol => p.advance(),
+                        TokenType::CurlyClose => {}
+                        _ => return Err(p.error_after("Expected Semicolon after Assembly Item", vec![TokenType::SemiCol]))
+                    }
+                }
+                p.advance();
+
+                Statement::AsmStmt(Box::new(AsmStmt {
+                    lines,
+                    operands,
+                    clobbers }))
+            }
+
+            Statement::BreakStmt(_, _) | Statement::ContinueStmt(_, _) => {
+                let keyword = p.peek(-1);
+
+                let mut label = None;
+                if let TokenType::Label(_) = p.peek(0).ttype {
+                    label = Some(p.peek(0));
+                    p.advance();
+                }
+
+                //the semicolon can be left out right before a closing brace
+                match p.peek(0).ttype {
+                    TokenType::SemiCol => p.advance(),
+                    TokenType::CurlyClose => {}
+                    _ => return Err(p.error_after("Expected Semicolon after Jump Statement", vec![TokenType::SemiCol]))
+                }
+
+                match self {
+                    Statement::BreakStmt(_, _) => Statement::BreakStmt(keyword, label),
+                    _ => Statement::ContinueStmt(keyword, label)
+                }
+            }
+
+            Statement::LoopStmt(_, _) => {
                 if p.peek(0).ttype != TokenType::CurlyOpen {
-                    return Err("Expected Block after Loop Statement")
+                    return Err(p.error("Expected Block after Loop Statement", vec![TokenType::CurlyOpen]))
                 }
 
                 p.advance();
                 let s = new_statement("Block").parse(p)?;
 
-                Statement::LoopStmt(Box::new(s))
+                Statement::LoopStmt(Box::new(s), None)
             }
 
             Statement::IfStmt(_) => 'b: {
                 let cond = new_expr("Base").parse(p)?;
 
                 if p.peek(0).ttype != TokenType::CurlyOpen {
-                    return Err("Expected Block after If Statement")
+                    return Err(p.error("Expected Block after If Statement", vec![TokenType::CurlyOpen]))
                 }
                 
                 p.advance();
@@ -424,12 +961,19 @@ impl Statement {
                 if p.peek(0).ttype == TokenType::Key("else".to_string()) {
                     p.advance();
 
-                    if p.peek(0).ttype != TokenType::CurlyOpen {return Err("Expected Block After Else Statement")}
+                    let false_b = if p.peek(0).ttype == TokenType::Key("if".to_string()) {
+                        p.advance();
+                        new_statement("IfStmt").parse(p)?
+                    } else {
+                        if p.peek(0).ttype != TokenType::CurlyOpen {
+                            return Err(p.error("Expected Block After Else Statement", vec![TokenType::CurlyOpen, TokenType::Key("if".to_string())]))
+                        }
 
-                    p.advance();
-                    let false_b = new_statement("Block").parse(p)?;
+                        p.advance();
+                        new_statement("Block").parse(p)?
+                    };
                     break 'b Statement::IfStmt(Box::new( CondStmt {
-                        cond: cond,
+                        cond,
                         true_branch: true_b,
                         false_branch: Some(false_b) }
                     ))
@@ -437,16 +981,16 @@ impl Statement {
 
 
                 Statement::IfStmt(Box::new(CondStmt {
-                    cond: cond,
+                    cond,
                     true_branch: true_b,
                     false_branch: None }))
             }
 
-            Statement::WhileStmt(_) => {
+            Statement::WhileStmt(_, _) => {
                 let cond = new_expr("Base").parse(p)?;
                 
                 if p.peek(0).ttype != TokenType::CurlyOpen {
-                    return Err("Expected Curly Brace after While Statement")
+                    return Err(p.error("Expected Curly Brace after While Statement", vec![TokenType::CurlyOpen]))
                 }
 
                 p.advance();
@@ -454,14 +998,69 @@ impl Statement {
                 let body = new_statement("Block").parse(p)?;
 
                 Statement::WhileStmt(Box::new(CondStmt {
-                    cond: cond,
+                    cond,
                     true_branch: body,
-                    false_branch: None }))
+                    false_branch: None }), None)
+            }
+
+            Statement::ForStmt(_) => {
+                if !matches!(p.peek(0).ttype, TokenType::Id(_)) {
+                    return Err(p.error("Expected Identifier for Loop Variable", vec![TokenType::Id(String::new())]))
+                }
+                let var = p.peek(0);
+                p.advance();
+
+                if p.peek(0).ttype != TokenType::Col {
+                    return Err(p.error("Expected Colon after Loop Variable", vec![TokenType::Col]).hint("loop variables need a type: for i: u16 in 0..n"))
+                }
+                p.advance();
+
+                let var_type = parse_type(p)?;
+
+                if p.peek(0).ttype != TokenType::Key("in".to_string()) {
+                    return Err(p.error("Expected In after Loop Variable Type", vec![TokenType::Key("in".to_string())]))
+                }
+                p.advance();
+
+                let start = new_expr("Base").parse(p)?;
+
+                let inclusive = match p.peek(0).ttype {
+                    TokenType::Range => false,
+                    TokenType::RangeIncl => true,
+                    _ => return Err(p.error("Expected Range in For Statement", vec![TokenType::Range, TokenType::RangeIncl]))
+                };
+                p.advance();
+
+                let end = new_expr("Base").parse(p)?;
+
+                //step is only a keyword here
+                let mut step = None;
+                if p.peek(0).ttype == TokenType::Id("step".to_string()) {
+                    p.advance();
+                    step = Some(new_expr("Base").parse(p)?);
+                }
+
+                if p.peek(0).ttype != TokenType::CurlyOpen {
+                    return Err(p.error("Expected Block after For Statement", vec![TokenType::CurlyOpen]))
+                }
+                p.advance();
+
+                let body = new_statement("Block").parse(p)?;
+
+                Statement::ForStmt(Box::new(ForStmt {
+                    var,
+                    var_type,
+                    start,
+                    end,
+                    inclusive,
+                    step,
+                    body,
+                    label: None }))
             }
 
             Statement::Block(v) => {
                 while p.peek(0).ttype != TokenType::CurlyClose {
-                    if p.peek(0).ttype == TokenType::EOF {return Err("Expected Closing Curly Bracket")}
+                    if p.peek(0).ttype == TokenType::EOF {return Err(p.error("Expected Closing Curly Bracket", vec![TokenType::CurlyClose]))}
                     
                     let s = new_statement("Base").parse(p)?;
                     v.push(s);
@@ -475,7 +1074,87 @@ impl Statement {
 
         };
 
-        return Ok(self.clone())
+        Ok(self.clone())
+    }
+}
+
+impl Statement {
+    //puts the outer doc comment in front of any inner doc comment the item already has
+    pub fn with_doc(mut self, outer: Option<String>) -> Statement {
+        let target = match &mut self {
+            Statement::FnDeclr(d) => &mut d.doc,
+            Statement::StructDeclr(d) => &mut d.doc,
+            Statement::EnumDeclr(d) => &mut d.doc,
+            Statement::VarDeclr(d) | Statement::ConstDeclr(d) | Statement::StaticDeclr(d, _) => &mut d.doc,
+            Statement::TypeAlias(_, _, doc) => doc,
+            _ => return self
+        };
+
+        *target = match (outer, target.take()) {
+            (Some(o), Some(i)) => Some(o + "\n" + &i),
+            (o, i) => o.or(i)
+        };
+
+        self
+    }
+
+    //the declarations that may appear outside of functions
+    pub fn is_item(&self) -> bool {
+        matches!(self,
+            Statement::FnDeclr(_) | Statement::StructDeclr(_) | Statement::EnumDeclr(_) |
+            Statement::TypeAlias(_, _, _) | Statement::ConstDeclr(_) | Statement::StaticDeclr(_, _) |
+            Statement::Error(_))
+    }
+
+    pub fn with_linkage(mut self, linkage: Linkage) -> Statement {
+        if let Statement::FnDeclr(d) = &mut self {
+            d.linkage = linkage;
+        }
+
+        self
+    }
+
+    pub fn with_label(mut self, label: Token) -> Statement {
+        match &mut self {
+            Statement::LoopStmt(_, l) | Statement::WhileStmt(_, l) => *l = Some(label),
+            Statement::ForStmt(d) => d.label = Some(label),
+            _ => {}
+        }
+
+        self
+    }
+}
+
+impl MatchPattern {
+    fn parse(p: &mut Parser) -> Result<MatchPattern, ParseError> {
+        if p.peek(0).ttype == TokenType::Id("_".to_string()) {
+            p.advance();
+            return Ok(MatchPattern::Wildcard(p.peek(-1)))
+        }
+
+        if matches!(p.peek(0).ttype, TokenType::Id(_))
+        && p.peek(1).ttype == TokenType::Col
+        && p.peek(2).ttype == TokenType::Col
+        && matches!(p.peek(3).ttype, TokenType::Id(_)) {
+            let enum_name = p.peek(0);
+            let variant = p.peek(3);
+            for _ in 0..4 {
+                p.advance();
+            }
+
+            return Ok(MatchPattern::Variant(enum_name, variant))
+        }
+
+        Err(p.error("Expected Enum Variant in Match Pattern", vec![TokenType::Id(String::new())]).hint("patterns look like Enum::Variant or _"))
+    }
+}
+
+impl std::fmt::Display for MatchPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchPattern::Variant(e, v) => write!(f, "{}::{}", e.data(), v.data()),
+            MatchPattern::Wildcard(_) => write!(f, "_"),
+        }
     }
 }
 
@@ -483,6 +1162,7 @@ impl std::fmt::Display for Statement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.clone() {
             Statement::Base => Ok(()),
+            Statement::Error(span) => write!(f, "<error at {}..{}>", span.start, span.end),
             Statement::Declr => Ok(()),
             Statement::Stmt => Ok(()),
             Statement::ExprStmt(s) => write!(f, "Expression Stmt: {};", s),
@@ -494,12 +1174,69 @@ impl std::fmt::Display for Statement {
                 //write!(f, "\n")?;
                 Ok(())
             },
-            Statement::VarDeclr(d) => write!(f, "declare {} type: {} value: {}", d.name.data(), d.var_type, d.value.unwrap_or(new_expr("Base"))),
-            Statement::LoopStmt(d) => write!(f, "Loop {}", *d),
-            Statement::IfStmt(d) => write!(f, "If {} then {}\nelse {}", d.cond, d.true_branch, d.false_branch.unwrap_or(new_statement("Base"))),
-            Statement::WhileStmt(d) => write!(f, "While {} do {}", d.cond, d.true_branch),
-            Statement::BreakStmt(_) => write!(f, "Break"),
-            Statement::FnDeclr(d) => write!(f, "define function: {}   params: {}  \nret type: {}   body: {}", d.name.ttype, d.params, d.ret_type, d.body),
+            Statement::VarDeclr(d) => write!(f, "{}declare {} type: {} value: {}", doc_prefix(&d.doc), d.name.data(), d.var_type, d.value.unwrap_or(new_expr("Base"))),
+            Statement::ConstDeclr(d) => write!(f, "{}declare const {} type: {} value: {}", doc_prefix(&d.doc), d.name.data(), d.var_type, d.value.unwrap_or(new_expr("Base"))),
+            Statement::TypeAlias(name, t, doc) => write!(f, "{}declare type {} = {}", doc_prefix(&doc), name.data(), t),
+            Statement::StaticDeclr(d, mutable) => {
+                let kind = if mutable {"static mut"} else {"static"};
+                match &d.value {
+                    Some(v) => write!(f, "{}declare {} {} type: {} value: {}", doc_prefix(&d.doc), kind, d.name.data(), d.var_type, v),
+                    None => write!(f, "{}declare {} {} type: {} zeroed", doc_prefix(&d.doc), kind, d.name.data(), d.var_type)
+                }
+            }
+            Statement::LoopStmt(d, l) => write!(f, "{}Loop {}", label_prefix(&l), *d),
+            Statement::IfStmt(d) => {
+                write!(f, "If {} then {}", d.cond, d.true_branch)?;
+
+                //else if chains are printed flat
+                let mut false_branch = d.false_branch;
+                while let Some(Statement::IfStmt(arm)) = false_branch {
+                    write!(f, "\nelse if {} then {}", arm.cond, arm.true_branch)?;
+                    false_branch = arm.false_branch;
+                }
+
+                write!(f, "\nelse {}", false_branch.unwrap_or(new_statement("Base")))
+            }
+            Statement::WhileStmt(d, l) => write!(f, "{}While {} do {}", label_prefix(&l), d.cond, d.true_branch),
+            Statement::ForStmt(d) => {
+                let range = if d.inclusive {"..="} else {".."};
+                write!(f, "{}For {}: {} in {}{}{}", label_prefix(&d.label), d.var.ttype, d.var_type, d.start, range, d.end)?;
+
+                if let Some(step) = d.step {
+                    write!(f, " step {}", step)?;
+                }
+                write!(f, " do {}", d.body)
+            }
+            Statement::MatchStmt(d) => {
+                write!(f, "Match {}", d.value)?;
+
+                for arm in d.arms {
+                    let patterns: Vec<String> = arm.patterns.iter().map(|p| p.to_string()).collect();
+                    write!(f, "\narm {} => {}", patterns.join(" | "), arm.body)?;
+                }
+                Ok(())
+            }
+            Statement::AsmStmt(d) => {
+                write!(f, "Asm")?;
+                for line in &d.lines {
+                    write!(f, "\nline {}", line.ttype)?;
+                }
+                for o in &d.operands {
+                    write!(f, "\n{:?} {} = {}", o.kind, o.name.data(), o.value)?;
+                }
+                if !d.clobbers.is_empty() {
+                    let clobbers: Vec<String> = d.clobbers.iter().map(|c| c.data()).collect();
+                    write!(f, "\nclobbers {}", clobbers.join(", "))?;
+                }
+                Ok(())
+            }
+            Statement::BreakStmt(_, l) => write!(f, "Break{}", l.map_or(String::new(), |l| format!(" {}", l.ttype))),
+            Statement::ContinueStmt(_, l) => write!(f, "Continue{}", l.map_or(String::new(), |l| format!(" {}", l.ttype))),
+            Statement::FnDeclr(d) if d.linkage == Linkage::Extern => write!(f, "{}declare extern function: {}   params: {}  \nret type: {}", doc_prefix(&d.doc), d.name.ttype, d.params, d.ret_type),
+            Statement::FnDeclr(d) => {
+                let export = if d.linkage == Linkage::Export {"export "} else {""};
+                write!(f, "{}define {}function: {}   params: {}  \nret type: {}   body: {}", doc_prefix(&d.doc), export, d.name.ttype, d.params, d.ret_type, d.body)
+            }
             Statement::ReturnStmt(_, d) => write!(f, "return {}", d),
             Statement::Parameters(d) => {
                 for p in d {
@@ -509,13 +1246,23 @@ impl std::fmt::Display for Statement {
             }
             Statement::Variant(v) => {
                 for i in v  {
-                    write!(f, "\nvariant: {}", i.ttype)?;
+                    write!(f, "\n{}variant: {}", doc_prefix(&i.doc), i.name.ttype)?;
                 }
 
                 Ok(())
             }
-            Statement::StructDeclr(s) => write!(f, "Declare Struct {} Params: {}", s.name.ttype, s.params),
-            Statement::EnumDeclr(e) => write!(f, "Declare Enum {} variants: {}", e.name.ttype, e.variants),
+            Statement::StructDeclr(s) => {
+                write!(f, "{}Declare Struct {} Params:", doc_prefix(&s.doc), s.name.ttype)?;
+
+                //same as Parameters, with each field's docs in front of it
+                if let Statement::Parameters(fields) = s.params {
+                    for (field, doc) in fields.iter().zip(&s.field_docs) {
+                        write!(f, "\n{}param name: {}   param type: {}", doc_prefix(doc), field.0.ttype, field.1)?;
+                    }
+                }
+                Ok(())
+            }
+            Statement::EnumDeclr(e) => write!(f, "{}Declare Enum {} variants: {}", doc_prefix(&e.doc), e.name.ttype, e.variants),
         }
     }
 }
@@ -525,8 +1272,87 @@ impl std::fmt::Display for DeclrType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.clone() {
             DeclrType::BasicType(t) => write!(f, "{}", t.ttype),
-            DeclrType::Array(t, s) => write!(f, "Array of type: {} Size: {}", t, s.ttype),
+            DeclrType::Array(t, s) => write!(f, "Array of type: {} Size: {}", t, s),
             DeclrType::Pointer(t) => write!(f, "Pointer at {}", *t),
+            DeclrType::Func(_, params, ret) => {
+                let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+                write!(f, "Function of ({}) returning {}", params.join(", "), ret)
+            }
         }
     }
-}
\ No newline at end of file
+}
+//docs are printed the way they were written, one /// per line
+fn doc_prefix(doc: &Option<String>) -> String {
+    match doc {
+        Some(d) => d.lines().map(|l| format!("/// {}\n", l)).collect(),
+        None => String::new()
+    }
+}
+
+fn label_prefix(label: &Option<Token>) -> String {
+    match label {
+        Some(l) => format!("{}: ", l.ttype),
+        None => String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    fn parse(text: &str) -> Vec<Statement> {
+        let tokens = Lexer::new(text.to_string(), 0).lex();
+        let mut p = Parser::new(tokens);
+        let program = p.parse();
+
+        assert!(p.errors.is_empty(), "{} did not parse", text);
+        program
+    }
+
+    #[test]
+    fn docs() {
+        let program = parse("/// a point\nstruct P {\n /// across\n x: u8,\n y: u8\n}\nenum E {\n /// first\n A,\n B\n}\n/// the limit\nconst MAX: u8 = 3;\n/// counts\nstatic mut n: u8;\n/// an id\ntype Id = u8;\nfn main() -> void {\n /// scratch\n let x: u8 = 1;\n}");
+
+        assert_eq!(program[0].to_string(), "/// a point\nDeclare Struct P Params:\n/// across\nparam name: x   param type: u8\nparam name: y   param type: u8");
+        assert!(program[1].to_string().contains("\n/// first\nvariant: A\nvariant: B"), "{}", program[1]);
+        assert!(program[2].to_string().starts_with("/// the limit\ndeclare const MAX"), "{}", program[2]);
+        assert!(program[3].to_string().starts_with("/// counts\ndeclare static mut n"), "{}", program[3]);
+        assert!(program[4].to_string().starts_with("/// an id\ndeclare type Id"), "{}", program[4]);
+        assert!(program[5].to_string().contains("/// scratch\ndeclare x"), "{}", program[5]);
+    }
+
+    #[test]
+    fn stray_docs() {
+        let tokens = Lexer::new("fn main() -> void {\n /// nothing\n main();\n}".to_string(), 0).lex();
+        let mut p = Parser::new(tokens);
+        p.parse();
+
+        assert_eq!(p.errors.len(), 1);
+    }
+
+    #[test]
+    fn blocks_are_not_struct_literals() {
+        let program = parse("fn main() -> void {\n let flag: u8 = 1;\n if flag {}\n while flag {}\n let p: P = P {x: 1};\n}");
+
+        let Statement::FnDeclr(main) = &program[0] else { panic!("{}", program[0]) };
+        let Statement::Block(body) = &main.body else { panic!("{}", main.body) };
+        assert!(matches!(body[1], Statement::IfStmt(_)), "{}", body[1]);
+        assert!(matches!(body[2], Statement::WhileStmt(_, _)), "{}", body[2]);
+        assert!(body[3].to_string().contains("P {x: 1}"), "{}", body[3]);
+    }
+
+    #[test]
+    fn recovery() {
+        let tokens = Lexer::new("fn main() -> void {\n let = 1;\n let y: u8 = ;\n main();\n}\nstruct {}\nfn g() -> void {}".to_string(), 0).lex();
+        let mut p = Parser::new(tokens);
+        let program = p.parse();
+
+        //every broken statement is reported and the ones after it still parse
+        assert_eq!(p.errors.len(), 3, "{:?}", p.errors);
+        let printed: Vec<String> = program.iter().map(|s| s.to_string()).collect();
+        assert!(printed[0].contains("<error at 25..26>\n<error at 43..44>\nExpression Stmt") && printed[0].contains("Expression Stmt"), "{}", printed[0]);
+        assert!(printed[1].starts_with("<error at"), "{}", printed[1]);
+        assert!(printed[2].starts_with("define function: g"), "{}", printed[2]);
+    }
+}
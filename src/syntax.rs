@@ -1,5 +1,8 @@
 mod scope;
 mod types;
+mod eval;
+
+use std::collections::{HashMap, HashSet};
 
 use crate::definitions::*;
 use crate::statement::*;
@@ -7,12 +10,33 @@ use crate::expression::*;
 
 use scope::*;
 use types::*;
+use eval::*;
+
+//what the checker worked out that code generation needs
+#[derive(Debug, Default)]
+pub struct CheckedInfo {
+    pub match_discriminants: Vec<(Span, Vec<(String, i128)>)>, //(match keyword, (variant, discriminant))
+    pub const_values: HashMap<Span, i128>, //uses of consts and sizeof
+    pub static_sizes: HashMap<Span, u16>, //static name, bytes it takes up
+    pub static_data: HashMap<Span, Vec<DataItem>>, //static name, its initial value in memory order
+    pub indirect_calls: HashSet<Span>, //opening parenthesis of calls through function pointers
+    pub pointer_fields: HashSet<Span>, //fields reached through a pointer to their struct
+}
+
+//one value in the data section
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataItem {
+    Value(u16, i128), //(bytes, value)
+    Label(String), //address of a static or function
+    Str(Vec<u8>), //address of a string literal in rodata
+}
 
-pub fn check_ast_syntax(ast: Vec<Statement>) -> Result<(), SyntaxErr> {
+pub fn check_ast_syntax(ast: Vec<Statement>) -> Result<CheckedInfo, SyntaxErr> {
     let mut ss = ScopeStack {
         stack: vec![],
         defined_types: vec![],
-        used_ids: vec![]};
+        used_ids: vec![],
+        info: CheckedInfo::default()};
 
 
     define_types_in_scope(&ast, &mut ss)?;
@@ -20,17 +44,129 @@ pub fn check_ast_syntax(ast: Vec<Statement>) -> Result<(), SyntaxErr> {
         statement.check_syntax(&mut ss)?;
     }
 
-    Ok(())
+    Ok(ss.info)
+}
+
+//resolves the aliases a pending alias refers to first, chain is the aliases being resolved
+//returns false and leaves the alias pending if it needs a struct or enum that isn't defined yet
+fn resolve_alias(name: &Token, pending: &mut Vec<(Token, DeclrType)>, chain: &mut Vec<String>, undefined: &Vec<String>, ss: &mut ScopeStack) -> Result<bool, SyntaxErr> {
+    chain.push(name.data());
+
+    let aliased = pending.iter().find(|a| a.0.data() == name.data()).unwrap().1.clone();
+
+    for used in type_names(&aliased) {
+        if chain.contains(&used.data()) {
+            chain.push(used.data());
+            return Err(SyntaxErr::AliasCycle(name.clone(), chain.clone()))
+        }
+
+        let ready = match pending.iter().any(|a| a.0.data() == used.data()) {
+            true => resolve_alias(&used, pending, chain, undefined, ss)?,
+            false => !undefined.contains(&used.data())
+        };
+
+        if !ready {
+            chain.pop();
+            return Ok(false)
+        }
+    }
+
+    if RESERVED_IDS.contains(&name.data().as_str()) {
+        return Err(SyntaxErr::ReservedID(name.clone()))
+    }
+
+    if ss.used_ids.contains(&name.data()) {
+        return Err(SyntaxErr::AlreadyDefined(name.clone()))
+    }
+
+    let var_type = match VarType::from(aliased.clone(), &ss.defined_types) {
+        Ok(t) => t,
+        Err(e) => return Err(SyntaxErr::UnknownType(aliased.get_token(), e))
+    };
+
+    let alias = UserType::Alias(AliasDef {
+        name: name.data(),
+        var_type });
+
+    ss.user_type_declr(alias);
+
+    pending.retain(|a| a.0.data() != name.data());
+    chain.pop();
+    Ok(true)
+}
+
+//every type name written in a declared type
+fn type_names(t: &DeclrType) -> Vec<Token> {
+    match t {
+        DeclrType::BasicType(name) => vec![name.clone()],
+        DeclrType::Array(inner, _) | DeclrType::Pointer(inner) => type_names(inner),
+        DeclrType::Func(_, params, ret_type) => {
+            let mut names: Vec<Token> = params.iter().flat_map(type_names).collect();
+            names.extend(type_names(ret_type));
+            names
+        }
+    }
 }
 
 fn define_types_in_scope(ast: &Vec<Statement>, ss: &mut ScopeStack) -> Result<(), SyntaxErr> {
     let mut types_to_recheck: Vec<UserStructDef> = vec![];
 
-    //to allow for pointers to the struct
-    let mut temp_defined_types: Vec<UserType> = ss.defined_types.clone();
+    //aliases can refer to aliases declared after them, so they are resolved on demand,
+    //and to structs and enums declared after them, so they wait until those are defined
+    let mut pending_aliases: Vec<(Token, DeclrType)> = vec![];
+    let mut undefined: Vec<String> = vec![];
+    for stmt in ast {
+        match stmt {
+            Statement::TypeAlias(name, t, _) => {
+                if pending_aliases.iter().any(|a| a.0.data() == name.data()) {
+                    return Err(SyntaxErr::AlreadyDefined(name.clone()))
+                }
+                pending_aliases.push((name.clone(), t.clone()));
+            }
+            Statement::StructDeclr(declr) => undefined.push(declr.name.data()),
+            Statement::EnumDeclr(declr) => undefined.push(declr.name.data()),
+            _ => {}
+        }
+    }
     
     for stmt in ast {
         match stmt {
+            Statement::TypeAlias(name, _, _) if pending_aliases.iter().any(|a| a.0.data() == name.data()) => {
+                resolve_alias(name, &mut pending_aliases, &mut vec![], &undefined, ss)?;
+            }
+
+            Statement::ConstDeclr(declr) => {
+                if RESERVED_IDS.contains(&declr.name.data().as_str()) {
+                    return Err(SyntaxErr::ReservedID(declr.name.clone()))
+                }
+
+                if ss.used_ids.contains(&declr.name.data()) {
+                    return Err(SyntaxErr::AlreadyDefined(declr.name.clone()))
+                }
+
+                let const_type = match VarType::from(declr.var_type.clone(), &ss.defined_types) {
+                    Ok(t) if t.is_integer() => t,
+                    Ok(t) => return Err(SyntaxErr::NotAnInteger(declr.var_type.get_token(), t)),
+                    Err(e) => return Err(SyntaxErr::UnknownType(declr.var_type.get_token(), e))
+                };
+
+                let value = eval_const(declr.value.as_ref().unwrap(), &ss.defined_types)?;
+
+                if !value.fits(&const_type) {
+                    return match value.var_type {
+                        Some(t) => Err(ss.type_mismatch(&declr.var_type, const_type, t)),
+                        None => Err(SyntaxErr::ConstOverflow(declr.name.clone()))
+                    }
+                }
+
+                let const_def = UserType::Const(ConstDef {
+                    name: declr.name.data(),
+                    var_type: const_type,
+                    value: value.value });
+
+                ss.user_type_declr(const_def);
+            }
+
             Statement::StructDeclr(declr) => {
                 let struct_name = declr.name.clone();
 
@@ -55,10 +191,10 @@ fn define_types_in_scope(ast: &Vec<Statement>, ss: &mut ScopeStack) -> Result<()
                     fields: params };
                 
                 if needs_rechecking {
-                    types_to_recheck.push(current_definition.clone());
-                    temp_defined_types.push(UserType::UserStruct(current_definition));
+                    types_to_recheck.push(current_definition);
                 } else {
                     ss.user_type_declr(UserType::UserStruct(current_definition));
+                    undefined.retain(|n| *n != struct_name.data());
                 }
             }
 
@@ -68,41 +204,92 @@ fn define_types_in_scope(ast: &Vec<Statement>, ss: &mut ScopeStack) -> Result<()
                     return Err(SyntaxErr::AlreadyDefined(declr.name.clone()))
                 }
 
-                let mut e_variants: Vec<String> = vec![];
+                let declared_base = match &declr.base_type {
+                    Some(t) => match VarType::from(t.clone(), &ss.defined_types) {
+                        Ok(base) if base.is_integer() => Some(base),
+                        Ok(base) => return Err(SyntaxErr::NotAnInteger(t.get_token(), base)),
+                        Err(e) => return Err(SyntaxErr::UnknownType(t.get_token(), e))
+                    },
+                    None => None
+                };
+
+                let mut e_variants: Vec<(String, i128)> = vec![];
+                let mut next: i128 = 0;
 
                 if let Statement::Variant(variants) = declr.variants.clone() {
                     for v in variants {
-                        e_variants.push(v.data());
+                        let value = match &v.value {
+                            Some(e) => eval_const(e, &ss.defined_types)?.value,
+                            None => next
+                        };
+
+                        if e_variants.iter().any(|d| d.1 == value) {
+                            return Err(SyntaxErr::DupDiscriminant(v.name))
+                        }
+
+                        if let Some(base) = &declared_base {
+                            if !base.fits(value) {
+                                return Err(SyntaxErr::DiscriminantOutOfRange(v.name, base.clone()))
+                            }
+                        }
+
+                        e_variants.push((v.name.data(), value));
+                        next = value + 1;
                     }
                 }
 
+                //without a declared type the enum is as wide as its discriminants need
+                let base = match declared_base {
+                    Some(base) => base,
+                    None => match smallest_int_type(e_variants.iter().map(|v| v.1)) {
+                        Some(base) => base,
+                        None => return Err(SyntaxErr::DiscriminantOutOfRange(declr.name.clone(), VarType::I64))
+                    }
+                };
+
                 let user_enum = UserType::UserEnum(UserEnumDef {
                     name: declr.name.data(),
+                    base: Box::new(base),
                     variants: e_variants });
 
-                ss.user_type_declr(user_enum.clone());
-                temp_defined_types.push(user_enum);
+                ss.user_type_declr(user_enum);
+                undefined.retain(|n| *n != declr.name.data());
             }
 
             _ => {}
         }
     }
 
+    //to allow for pointers to the struct, the structs left over are defined with their
+    //fields unresolved first, along with the aliases that lead to them
+    let mut temp_defined_types: Vec<UserType> = ss.defined_types.clone();
+    temp_defined_types.extend(types_to_recheck.iter().map(|s| UserType::UserStruct(s.clone())));
+
+    let mut temp_aliases = pending_aliases.clone();
+    while let Some(n) = temp_aliases.iter().position(|a| VarType::from(a.1.clone(), &temp_defined_types).is_ok()) {
+        let (name, t) = temp_aliases.remove(n);
+        let var_type = VarType::from(t, &temp_defined_types).unwrap();
+
+        temp_defined_types.push(UserType::Alias(AliasDef {
+            name: name.data(),
+            var_type }));
+    }
+
     for checking_struct in types_to_recheck.iter_mut() {
         for field in checking_struct.fields.iter_mut() {
             if let FieldType::Undefined(field_type) = field.1.clone() {
-                if field_type.to_string() == checking_struct.name {
-                    if let DeclrType::BasicType(basic) = field_type {
-                        return Err(SyntaxErr::RecursiveStruct(basic))
-                    }
-                    panic!("should not have gotten here")
-                }
-
                 let resolved_field_type = match VarType::from(field_type.clone(), &temp_defined_types) {
                     Ok(t) => t,
                     Err(e) => {return Err(SyntaxErr::UnknownType(field_type.get_token(), e))}
                 };
 
+                //by name or through an alias, only a pointer can lead back to the struct
+                if let VarType::UserStruct(s) = &resolved_field_type {
+                    if s.name == checking_struct.name {
+                        return Err(SyntaxErr::RecursiveStruct(field_type.get_token()))
+                    }
+                }
+
                 field.1 = FieldType::Defined(resolved_field_type);
             }
         }
@@ -110,6 +297,11 @@ fn define_types_in_scope(ast: &Vec<Statement>, ss: &mut ScopeStack) -> Result<()
         ss.user_type_declr(UserType::UserStruct(checking_struct.clone()));
     }
 
+    //the aliases still pending lead to the structs that were just defined
+    while let Some((name, _)) = pending_aliases.first().cloned() {
+        resolve_alias(&name, &mut pending_aliases, &mut vec![], &vec![], ss)?;
+    }
+
     Ok(())
 }
 
@@ -137,6 +329,10 @@ impl Statement {
                     return Err(SyntaxErr::AlreadyDefined(declr.name))
                 }
                 
+                if let Err(e) = VarType::from(declr.ret_type.clone(), &ss.defined_types) {
+                    return Err(SyntaxErr::UnknownType(declr.ret_type.get_token(), e))
+                }
+
                 let mut param_names: Vec<Token> = vec![];
                 ss.fn_declr(*declr.clone());
                 ss.enter_func_def();
@@ -167,7 +363,9 @@ impl Statement {
                 }
 
                 
-                declr.body.check_syntax(ss)?;
+                if declr.linkage != Linkage::Extern {
+                    declr.body.check_syntax(ss)?;
+                }
                 ss.leave_func_def();
             }
 
@@ -191,21 +389,73 @@ impl Statement {
                     //println!("declared type: {:?}   value: {:#?}    value type: {:?}", declared_type, value, value_type);
                     ss.var_declr(declr.name.data(), declared_type.clone());
 
-                    if value_type != declared_type{
-                        return Err(SyntaxErr::WrongType(declared_type, value_type))
+                    if !assignable(&declared_type, &value, &value_type) {
+                        return Err(ss.type_mismatch(&declr.var_type, declared_type, value_type))
                     }
                 }
 
                 ss.var_declr(declr.name.data(), declared_type);
             }
 
+            //already reported by the parser
+            Self::Error(_) => {}
+
             Self::StructDeclr(_) => {}
             
             Self::EnumDeclr(_) => {}
 
-            Self::LoopStmt(body) => {
-                ss.enter_breakable();
+            Self::ConstDeclr(_) => {}
+
+            Self::TypeAlias(_, _, _) => {}
+
+            Self::StaticDeclr(declr, mutable) => {
+                if ss.get_nearest_function().is_some() {
+                    return Err(SyntaxErr::StaticInFunction(declr.name))
+                }
+
+                if RESERVED_IDS.contains(&declr.name.data().as_str()) {
+                    return Err(SyntaxErr::ReservedID(declr.name))
+                }
+
+                if ss.used_ids.contains(&declr.name.data()) {
+                    return Err(SyntaxErr::AlreadyDefined(declr.name))
+                }
+
+                let declared_type: VarType = match VarType::from(declr.var_type.clone(), &ss.defined_types){
+                    Ok(t) => t,
+                    Err(e) => return Err(SyntaxErr::UnknownType(declr.var_type.get_token(), e))
+                };
+
+                //the initial value is stored in the data section so it has to be known at compile time
+                if let Some(value) = &declr.value {
+                    let value_type = value.check_syntax(ss)?;
+
+                    if !assignable(&declared_type, value, &value_type) {
+                        return Err(ss.type_mismatch(&declr.var_type, declared_type, value_type))
+                    }
+
+                    if !static_init(value, ss) {
+                        return Err(SyntaxErr::NotConstant(value.get_token()))
+                    }
+
+                    let mut items: Vec<DataItem> = vec![];
+                    static_data(&declared_type, value, ss, &mut items);
+                    ss.info.static_data.insert(declr.name.span, items);
+                }
+
+                let size = match declared_type.size() {
+                    Some(size) => size,
+                    None => return Err(SyntaxErr::TypeTooLarge(declr.var_type.get_token()))
+                };
+
+                ss.info.static_sizes.insert(declr.name.span, size);
+                ss.static_declr(declr.name.data(), declared_type, mutable);
+            }
+
+            Self::LoopStmt(body, label) => {
+                enter_loop(ss, label)?;
                 body.check_syntax(ss)?;
+                ss.leave_breakable();
             }
 
             Self::IfStmt(stmt) => {
@@ -216,18 +466,175 @@ impl Statement {
                 }
             }
             
-            Self::WhileStmt(stmt) => {
+            Self::WhileStmt(stmt, label) => {
                 stmt.cond.check_syntax(ss)?;
-                ss.enter_breakable();
+                enter_loop(ss, label)?;
                 stmt.true_branch.check_syntax(ss)?;
+                ss.leave_breakable();
             }
             
-            Self::BreakStmt(t) => {
-                if ss.inside_breakable() {
-                    return Ok(())
+            Self::ForStmt(stmt) => {
+                //bounds are evaluated before the loop variable exists
+                let var_type = match VarType::from(stmt.var_type.clone(), &ss.defined_types) {
+                    Ok(t) => t,
+                    Err(e) => return Err(SyntaxErr::UnknownType(stmt.var_type.get_token(), e))
+                };
+
+                if !var_type.is_integer() {
+                    return Err(SyntaxErr::NotAnInteger(stmt.var, var_type))
+                }
+
+                let mut bounds = vec![&stmt.start, &stmt.end];
+                if let Some(step) = &stmt.step {
+                    bounds.push(step);
+                }
+
+                for bound in bounds {
+                    let bound_type = bound.check_syntax(ss)?;
+                    if !assignable(&var_type, bound, &bound_type) {
+                        return Err(SyntaxErr::WrongType(var_type, bound_type))
+                    }
+                }
+
+                //a step that is known to be zero or negative would never reach the end
+                if let Some(step) = &stmt.step {
+                    if eval_const(step, &ss.defined_types).is_ok_and(|v| v.value <= 0) {
+                        return Err(SyntaxErr::InvalidStep(step.get_token()))
+                    }
+                }
+
+                if RESERVED_IDS.contains(&stmt.var.data().as_str()) {
+                    return Err(SyntaxErr::ReservedID(stmt.var))
                 }
 
-                return Err(SyntaxErr::BreakOutsideLoop(t.clone()))
+                //the loop variable only lives inside the body
+                ss.enter_scope();
+                ss.var_declr(stmt.var.data(), var_type);
+                enter_loop(ss, stmt.label)?;
+                stmt.body.check_syntax(ss)?;
+                ss.leave_breakable();
+                ss.leave_scope();
+            }
+
+            Self::AsmStmt(stmt) => {
+                let mut names: Vec<String> = vec![];
+
+                for operand in &stmt.operands {
+                    if names.contains(&operand.name.data()) {
+                        return Err(SyntaxErr::DupAsmOperand(operand.name.clone()))
+                    }
+                    names.push(operand.name.data());
+
+                    //outputs are written back like an assignment
+                    if operand.kind != AsmOperandKind::In {
+                        if !operand.value.is_place() {
+                            return Err(SyntaxErr::NotAddressable(operand.value.get_token()))
+                        }
+
+                        check_writable(&operand.value, ss)?;
+                    }
+
+                    //operands have to fit a register
+                    let operand_type = operand.value.check_syntax(ss)?;
+                    let fits_register = operand_type.is_integer() || matches!(operand_type,
+                        VarType::Pointer(_) | VarType::UserEnum(_) | VarType::Func(_, _));
+
+                    if !fits_register || operand_type.size().is_none_or(|size| size > 2) {
+                        return Err(SyntaxErr::InvalidAsmOperand(operand.name.clone(), operand_type))
+                    }
+                }
+
+                let mut clobbered: Vec<String> = vec![];
+                for clobber in &stmt.clobbers {
+                    if clobbered.contains(&clobber.data()) {
+                        return Err(SyntaxErr::DupClobber(clobber.clone()))
+                    }
+                    clobbered.push(clobber.data());
+                }
+
+                for line in &stmt.lines {
+                    let pieces = match asm_template(&line.data()) {
+                        Some(pieces) => pieces,
+                        None => return Err(SyntaxErr::BadAsmTemplate(line.clone()))
+                    };
+
+                    for piece in pieces {
+                        if let AsmPiece::Operand(name) = piece {
+                            if !names.contains(&name) {
+                                return Err(SyntaxErr::UnknownAsmOperand(line.clone(), name))
+                            }
+                        }
+                    }
+                }
+            }
+
+            Self::MatchStmt(stmt) => {
+                let user_enum = match stmt.value.check_syntax(ss)? {
+                    VarType::UserEnum(e) => e,
+                    t => return Err(SyntaxErr::NotAnEnum(stmt.value.get_token(), t))
+                };
+
+                ss.info.match_discriminants.push((stmt.keyword.span, user_enum.variants.clone()));
+
+                let mut covered: Vec<String> = vec![];
+                let mut wildcard = false;
+
+                for arm in stmt.arms.iter() {
+                    for pattern in arm.patterns.iter() {
+                        match pattern {
+                            MatchPattern::Variant(e_name, variant) => {
+                                if e_name.data() != user_enum.name {
+                                    return match ss.get_user_enum(e_name.data()) {
+                                        Some(other) => Err(SyntaxErr::WrongType(VarType::UserEnum(user_enum), VarType::UserEnum(other))),
+                                        None => Err(SyntaxErr::UnknownType(e_name.clone(), "Undefined Enum"))
+                                    }
+                                }
+
+                                if !user_enum.check_variant(variant.data()) {
+                                    return Err(SyntaxErr::UnknownVariant(variant.clone()))
+                                }
+
+                                if wildcard || covered.contains(&variant.data()) {
+                                    return Err(SyntaxErr::UnreachableArm(variant.clone()))
+                                }
+                                covered.push(variant.data());
+                            }
+
+                            MatchPattern::Wildcard(t) => {
+                                if wildcard || covered.len() == user_enum.variants.len() {
+                                    return Err(SyntaxErr::UnreachableArm(t.clone()))
+                                }
+                                wildcard = true;
+                            }
+                        }
+                    }
+
+                    arm.body.check_syntax(ss)?;
+                }
+
+                let missing: Vec<String> = user_enum.variants.iter()
+                    .filter(|v| !covered.contains(&v.0))
+                    .map(|v| v.0.clone())
+                    .collect();
+
+                if !wildcard && !missing.is_empty() {
+                    return Err(SyntaxErr::NonExhaustive(stmt.keyword, missing))
+                }
+            }
+
+            Self::BreakStmt(t, label) | Self::ContinueStmt(t, label) => {
+                if !ss.inside_breakable(None) {
+                    return match self {
+                        Self::BreakStmt(_, _) => Err(SyntaxErr::BreakOutsideLoop(t)),
+                        _ => Err(SyntaxErr::ContinueOutsideLoop(t))
+                    }
+                }
+
+                if let Some(label) = label {
+                    if !ss.inside_breakable(Some(label.data())) {
+                        return Err(SyntaxErr::UnknownLabel(label))
+                    }
+                }
             }
 
             Self::ReturnStmt(t, d) => {
@@ -237,12 +644,12 @@ impl Statement {
 
                     match f {
                         Some(declr) => {
-                            let ret_type = VarType::from(declr.ret_type, &ss.defined_types).expect("should have been handled");
+                            let ret_type = VarType::from(declr.ret_type.clone(), &ss.defined_types).expect("should have been handled");
                             
-                            if ret_type == actual_return_type {
+                            if assignable(&ret_type, &d, &actual_return_type) {
                                 return Ok(())
                             } else {
-                                return Err(SyntaxErr::WrongType(ret_type, actual_return_type))
+                                return Err(ss.type_mismatch(&declr.ret_type, ret_type, actual_return_type))
                             }
                         }
                         None => return Err(SyntaxErr::ReturnOutsideFunc(t))
@@ -268,123 +675,183 @@ impl Expr {
     fn check_syntax(&self, ss: &mut ScopeStack) -> Result<VarType, SyntaxErr> {
         match self.clone() {
             Self::Assign(e) => {
+                check_writable(&e.left, ss)?;
+
                 let left_type = e.left.check_syntax(ss)?;
                 let right_type = e.right.check_syntax(ss)?;
 
-                if left_type != right_type{
+                if !assignable(&left_type, &e.right, &right_type) {
                     return Err(SyntaxErr::WrongType(right_type, left_type))
                 }
 
-                return Ok(right_type)
+                Ok(right_type)
             }
 
             Self::Equality(e) => {
                 let left_type = e.left.check_syntax(ss)?;
                 let right_type = e.right.check_syntax(ss)?;
 
-                if left_type != right_type {
-                    return Err(SyntaxErr::WrongType(right_type, left_type))
+                let left_type = operand_type(&e, left_type, right_type)?;
+
+                if matches!(left_type, VarType::UserStruct(_) | VarType::Array(_, _) | VarType::Void) {
+                    return Err(SyntaxErr::InvalidOperand(e.operator, left_type))
                 }
 
-                return Ok(left_type)
+                Ok(VarType::U8)
             }
 
             Self::Comparison(e) => {
                 let left_type = e.left.check_syntax(ss)?;
                 let right_type = e.right.check_syntax(ss)?;
 
-                if left_type != right_type {
-                    return Err(SyntaxErr::WrongType(right_type, left_type))
+                let left_type = operand_type(&e, left_type, right_type)?;
+
+                if !left_type.is_integer() && !matches!(left_type, VarType::Pointer(_)) {
+                    return Err(SyntaxErr::InvalidOperand(e.operator, left_type))
                 }
 
-                return Ok(left_type)
+                Ok(VarType::U8)
             }
 
-            Self::Term(e) => {
-                let left_type = e.left.check_syntax(ss)?;
-                let right_type = e.right.check_syntax(ss)?;
-
-                if left_type != right_type {
-                    return Err(SyntaxErr::WrongType(right_type, left_type))
+            Self::LogicOr(e) | Self::LogicAnd(e) => {
+                for operand in [&e.left, &e.right] {
+                    let t = operand.check_syntax(ss)?;
+                    if !t.is_integer() {
+                        return Err(SyntaxErr::InvalidOperand(e.operator.clone(), t))
+                    }
                 }
 
-                return Ok(left_type)
+                Ok(VarType::U8)
             }
 
-            Self::Shift(e) => {
+            Self::BitOr(e) | Self::BitXor(e) | Self::BitAnd(e) |
+            Self::Shift(e) | Self::Term(e) | Self::Factor(e) => {
                 let left_type = e.left.check_syntax(ss)?;
                 let right_type = e.right.check_syntax(ss)?;
 
-                if left_type != right_type {
-                    return Err(SyntaxErr::WrongType(right_type, left_type))
+                let left_type = operand_type(&e, left_type, right_type)?;
+
+                if !left_type.is_integer() {
+                    return Err(SyntaxErr::InvalidOperand(e.operator, left_type))
                 }
 
-                return Ok(left_type)
+                Ok(left_type)
             }
 
             Self::Unary(e) => {
-                return Ok(e.right.check_syntax(ss)?)
+                //a minus directly on a literal belongs to the literal, so -128i8 is in range
+                if let Some(literal) = negated_literal(&e) {
+                    return match literal.var_type() {
+                        Some(t) => {
+                            ss.info.const_values.insert(e.operator.span, literal.value);
+                            Ok(t)
+                        }
+                        None => Err(SyntaxErr::LiteralErr(e.right.get_token()))
+                    }
+                }
+
+                let right_type = e.right.check_syntax(ss)?;
+
+                if !right_type.is_integer() {
+                    return Err(SyntaxErr::InvalidOperand(e.operator, right_type))
+                }
+
+                //an unsigned value has no negative
+                if e.operator.data() == "-" && !right_type.fits(-1) {
+                    return Err(SyntaxErr::InvalidOperand(e.operator, right_type))
+                }
+
+                //logical not gives a truth value, - and ~ keep the type
+                if e.operator.data() == "!" {
+                    return Ok(VarType::U8)
+                }
+
+                Ok(right_type)
             }
 
             Self::Cast(cast) => {
-                // no casting to a struct
+                let value_type = cast.value.check_syntax(ss)?;
 
-                match VarType::from(cast.to_type.clone(), &ss.defined_types) {
-                    Ok(t) => Ok(t),
+                let to_type = match VarType::from(cast.to_type.clone(), &ss.defined_types) {
+                    Ok(t) => t,
                     Err(e) => return Err(SyntaxErr::UnknownType(cast.to_type.get_token(), e))
+                };
+
+                if !castable(&value_type, &to_type, &cast.value) {
+                    return Err(SyntaxErr::InvalidCast(cast.to_type.get_token(), Box::new((value_type, to_type))))
                 }
 
+                //a constant cast to an enum has to be one of its discriminants,
+                //other values are only known at runtime and a match guards against them
+                if let VarType::UserEnum(user_enum) = &to_type {
+                    if value_type != to_type {
+                        if let Ok(v) = eval_const(&cast.value, &ss.defined_types) {
+                            if !user_enum.variants.iter().any(|d| d.1 == v.value) {
+                                return Err(SyntaxErr::UnknownDiscriminant(cast.value.get_token(), v.value))
+                            }
+                        }
+                    }
+                }
+
+                Ok(to_type)
             }
 
             Self::FnCall(call) => {
-                let template = match ss.get_fn(call.name.data()) {
+                let template = match direct_callee(&call.callee, ss) {
                     Some(t) => t,
-                    None => return Err(SyntaxErr::Undeclared(call.name))
+                    None => {
+                        ss.info.indirect_calls.insert(call.paren.span);
+                        return check_indirect_call(&call, ss)
+                    }
                 };
 
                 //check arg number
                 if call.args.len() != template.params.get_param_vec().len() {
-                    return Err(SyntaxErr::WrongArgN(call.name.clone()))
+                    return Err(SyntaxErr::WrongArgN(call.callee.get_token()))
                 }
 
                 //check args
                 for n in 0..call.args.len() {
                     let calling_type = call.args[n].check_syntax(ss)?;
 
-                    let expected_type = template.params.get_param_vec()[n].1.clone();
-                    let expected_type = match VarType::from(expected_type.clone(), &vec![]) {
+                    let declared_type = template.params.get_param_vec()[n].1.clone();
+                    let expected_type = match VarType::from(declared_type.clone(), &ss.defined_types) {
                         Ok(t) => t,
-                        Err(e) => return Err(SyntaxErr::UnknownType(expected_type.get_token(), e))
+                        Err(e) => return Err(SyntaxErr::UnknownType(declared_type.get_token(), e))
                     };
 
-                    if calling_type != expected_type {
-                        return Err(SyntaxErr::WrongType(expected_type, calling_type))
+                    if !assignable(&expected_type, &call.args[n], &calling_type) {
+                        return Err(ss.type_mismatch(&declared_type, expected_type, calling_type))
                     }
                 }
 
-                Ok(VarType::from(template.ret_type, &vec![]).expect("template should have been checked first"))
+                Ok(VarType::from(template.ret_type, &ss.defined_types).expect("template should have been checked first"))
             }
 
             Self::Ref(r) => {
-                if r.operator.data().as_str() == "*" {
-                    //code is incorrect, need to add pointer as a type that points to a type
-                    let right_type = match ss.get_var_t(r.right.data()) {
-                        Some(t) => t,
-                        None => return Err(SyntaxErr::Undeclared(r.right))
-                    };
+                let right_type = r.right.check_syntax(ss)?;
 
+                if r.operator.data().as_str() == "*" {
                     if let VarType::Pointer(p) = right_type {
                         return Ok(*p.clone())
                     }
 
-                    return Err(SyntaxErr::NotDerefAble(r.right))
+                    return Err(SyntaxErr::NotDerefAble(r.right.get_token()))
                 }
 
                 if r.operator.data().as_str() == "&" {
-                    let right_type = match ss.get_var_t(r.right.data()) {
-                        Some(t) => t,
-                        None => return Err(SyntaxErr::Undeclared(r.right))
-                    };
+                    //only things with an address, not temporaries
+                    if !r.right.is_place() {
+                        return Err(SyntaxErr::NotAddressable(r.right.get_token()))
+                    }
+
+                    //consts are replaced by their value and a function's name already is its address
+                    if let Some(id) = assigned_var(&r.right, ss)? {
+                        let is_fn = ss.get_var_t(id.data()).is_none() && ss.get_fn(id.data()).is_some();
+                        if ss.get_const(id.data()).is_some() || is_fn {
+                            return Err(SyntaxErr::NotAddressable(id))
+                        }
+                    }
 
                     return Ok(VarType::Pointer(Box::new(right_type)))
                 }
@@ -395,61 +862,33 @@ impl Expr {
             Self::Primary(e) => {
                 match *e.clone() {
                     PrimaryExpr::Grouping(g) => {
-                        return Ok(g.check_syntax(ss)?);
+                        g.check_syntax(ss)
                     }
 
                     PrimaryExpr::Literal(l) => {
-                        //needs to be expanded
-                        let mut signed = false;
-                        if l.data().as_bytes()[0] == b'-' {
-                            signed = true;
+                        if let TokenType::Char(_) = l.ttype {
+                            return Ok(VarType::U8)
                         }
 
-                        if !signed {
-                            let number = match l.data().parse::<u64>() {
-                                Ok(t) => t,
-                                Err(_) => return Err(SyntaxErr::LiteralErr(l.clone()))
-                            };
-
-                            if number <= u64::MAX {
-                                if number <= u32::MAX.into() {
-                                    if number <= u16::MAX.into() {
-                                        if number <= u8::MAX.into() {
-                                            return Ok(VarType::U8)
-                                        }
-                                        
-                                        return Ok(VarType::U16)
-                                    }
-
-                                    return Ok(VarType::U32)
-                                }
-
-                                return Ok(VarType::U64)
+                        if let TokenType::Str(bytes) = l.ttype.clone() {
+                            return match u16::try_from(bytes.len()) {
+                                Ok(len) => Ok(VarType::Array(Box::new(VarType::U8), len)),
+                                Err(_) => Err(SyntaxErr::LiteralErr(l))
                             }
                         }
 
-                        let number = match l.data().parse::<i64>() {
-                            Ok(t) => t,
-                            Err(_) => return Err(SyntaxErr::LiteralErr(l.clone()))
+                        let literal = match IntLiteral::parse(&l.data()) {
+                            Some(t) => t,
+                            None => return Err(SyntaxErr::LiteralErr(l))
                         };
 
-                        if number <= i64::MAX && number >= i64::MIN {
-                            if number <= i32::MAX.into() && number >= i32::MIN.into() {
-                                if number <= i16::MAX.into() && number >= i16::MIN.into() {
-                                    if number <= i8::MAX.into() && number >= i8::MIN.into() {
-                                        return Ok(VarType::I8)
-                                    }
-                                    
-                                    return Ok(VarType::I16)
-                                }
-
-                                return Ok(VarType::I32)
+                        match literal.var_type() {
+                            Some(t) => {
+                                ss.info.const_values.insert(l.span, literal.value);
+                                Ok(t)
                             }
-
-                            return Ok(VarType::I64)
+                            None => Err(SyntaxErr::LiteralErr(l))
                         }
-
-                        panic!("Number literal error")
                     }
 
                     PrimaryExpr::Id(id) => {
@@ -457,6 +896,18 @@ impl Expr {
                             return Ok(VarType::Void)
                         }
                         
+                        if let Some(c) = ss.get_const(id.data()) {
+                            ss.info.const_values.insert(id.span, c.value);
+                            return Ok(c.var_type)
+                        }
+
+                        //a function's name is its address
+                        if ss.get_var_t(id.data()).is_none() {
+                            if let Some(f) = ss.get_fn(id.data()) {
+                                return fn_type(&f, ss)
+                            }
+                        }
+
                         let id_type;
                         
                         match ss.get_var_t(id.data()) {
@@ -464,13 +915,17 @@ impl Expr {
                             None => {return Err(SyntaxErr::Undeclared(id))}
                         }
 
-                        return Ok(id_type)
+                        Ok(id_type)
                     }
                 
-                    PrimaryExpr::StructField(s_name, s_field) => {
-                        let var_type = match ss.get_var_t(s_name.data()) {
-                            Some(t) => t,
-                            None => {return Err(SyntaxErr::Undeclared(s_name))}
+                    PrimaryExpr::StructField(s, s_field) => {
+                        //fields are reached through a pointer to a struct without an explicit *
+                        let var_type = match s.check_syntax(ss)? {
+                            VarType::Pointer(t) => {
+                                ss.info.pointer_fields.insert(s_field.span);
+                                *t
+                            }
+                            t => t
                         };
 
                         if let VarType::UserStruct(user_s) = var_type {
@@ -484,7 +939,7 @@ impl Expr {
                             return Ok(field_type.unwrap())
                         }
                         
-                        return Err(SyntaxErr::NotAStruct(s_name))
+                        Err(SyntaxErr::NotAStruct(s.get_token()))
                     }
 
                     PrimaryExpr::EnumVariant(e_name, e_variant) => {
@@ -493,31 +948,134 @@ impl Expr {
                             None => return Err(SyntaxErr::UnknownType(e_name, "Undefined Enum"))
                         };
 
-                        if !user_enum.check_variant(e_variant.data()) {
-                            return Err(SyntaxErr::UnknownVariant(e_variant))
-                        }
+                        let discriminant = match user_enum.variants.iter().find(|v| v.0 == e_variant.data()) {
+                            Some(v) => v.1,
+                            None => return Err(SyntaxErr::UnknownVariant(e_variant))
+                        };
 
-                        return Ok(VarType::UserEnum(user_enum))
+                        ss.info.const_values.insert(e_variant.span, discriminant);
+                        Ok(VarType::UserEnum(user_enum))
                     }
                 
-                    PrimaryExpr::ArrayAccess(a_name, a_index) => {
-                        let array = match ss.get_var_t(a_name.data()) {
-                            Some(t) => t,
-                            None => return Err(SyntaxErr::Undeclared(a_name))
-                        };
-
-                        let element_type = match array {
+                    PrimaryExpr::ArrayAccess(array, a_index) => {
+                        let element_type = match array.check_syntax(ss)? {
                             VarType::Array(t, _) => *t,
                             VarType::Pointer(t) => *t,
-                            _ => return Err(SyntaxErr::NotAnArray(a_name))
+                            _ => return Err(SyntaxErr::NotAnArray(array.get_token()))
                         };
                         
                         let index_type = a_index.check_syntax(ss)?;
-                        if index_type != VarType::U16 {
+                        if !assignable(&VarType::U16, &a_index, &index_type) {
                             return Err(SyntaxErr::WrongType(VarType::U16, index_type))
                         }
                         
-                        return Ok(element_type)
+                        Ok(element_type)
+                    }
+
+                    PrimaryExpr::StructLit(name, fields) => {
+                        let user_s = match ss.get_user_struct(name.data()) {
+                            Some(t) => t,
+                            None => return Err(SyntaxErr::UnknownType(name, "Undefined Struct"))
+                        };
+
+                        for (n, (field, value)) in fields.iter().enumerate() {
+                            if fields[..n].iter().any(|(f, _)| f.data() == field.data()) {
+                                return Err(SyntaxErr::DupField(field.clone()))
+                            }
+
+                            let field_type = match user_s.get_field_type(field.data()) {
+                                Some(t) => t.unwrap(),
+                                None => return Err(SyntaxErr::UnknownField(field.clone()))
+                            };
+
+                            let value_type = value.check_syntax(ss)?;
+                            if !assignable(&field_type, value, &value_type) {
+                                return Err(SyntaxErr::WrongType(field_type, value_type))
+                            }
+                        }
+
+                        for (field, _) in user_s.fields.iter() {
+                            if !fields.iter().any(|(f, _)| f.data() == *field) {
+                                return Err(SyntaxErr::MissingField(name, field.clone()))
+                            }
+                        }
+
+                        Ok(VarType::UserStruct(user_s))
+                    }
+
+                    PrimaryExpr::ArrayLit(elements) => {
+                        //unsuffixed literals take on the type of the other elements
+                        let mut element_type: Option<VarType> = None;
+                        let mut literals: Vec<(Token, i128)> = vec![];
+
+                        for e in elements.iter() {
+                            let t = e.check_syntax(ss)?;
+
+                            if let Some(v) = untyped_literal(e) {
+                                literals.push((e.get_token(), v));
+                                continue;
+                            }
+
+                            match &element_type {
+                                Some(expected) if *expected != t => return Err(SyntaxErr::WrongType(expected.clone(), t)),
+                                _ => element_type = Some(t)
+                            }
+                        }
+
+                        let element_type = match element_type {
+                            Some(t) => t,
+                            None => smallest_int_type(literals.iter().map(|l| l.1))
+                                .ok_or(SyntaxErr::LiteralErr(literals[0].0.clone()))?
+                        };
+
+                        for (token, v) in literals {
+                            if !element_type.fits(v) {
+                                return Err(SyntaxErr::LiteralErr(token))
+                            }
+                        }
+
+                        match u16::try_from(elements.len()) {
+                            Ok(len) => Ok(VarType::Array(Box::new(element_type), len)),
+                            Err(_) => Err(SyntaxErr::LiteralErr(elements[0].get_token()))
+                        }
+                    }
+
+                    PrimaryExpr::ArrayRepeat(value, count) => {
+                        let element_type = value.check_syntax(ss)?;
+
+                        //checked as well so the consts it uses are recorded for cgen
+                        count.check_syntax(ss)?;
+
+                        match eval_array_len(&count, &ss.defined_types) {
+                            Ok(len) => Ok(VarType::Array(Box::new(element_type), len)),
+                            Err(e) => Err(SyntaxErr::UnknownType(count.get_token(), e))
+                        }
+                    }
+
+                    //the size of a value comes from its checked type, so it works on variables too
+                    PrimaryExpr::SizeOf(keyword, SizeOfArg::Expr(value)) => {
+                        let value_type = match value.as_type_name() {
+                            Some(t) if !ss.is_value(&t.get_token()) => match VarType::from(t.clone(), &ss.defined_types) {
+                                Ok(t) => t,
+                                Err(e) => return Err(SyntaxErr::UnknownType(t.get_token(), e))
+                            },
+                            _ => value.check_syntax(ss)?
+                        };
+
+                        let size = match value_type.size() {
+                            Some(size) => size,
+                            None => return Err(SyntaxErr::TypeTooLarge(value.get_token()))
+                        };
+
+                        ss.info.const_values.insert(keyword.span, size.into());
+                        Ok(VarType::U16)
+                    }
+
+                    PrimaryExpr::SizeOf(keyword, _) | PrimaryExpr::AlignOf(keyword, _) |
+                    PrimaryExpr::OffsetOf(keyword, _, _) => {
+                        let value = eval_const(self, &ss.defined_types)?.value;
+                        ss.info.const_values.insert(keyword.span, value);
+                        Ok(VarType::U16)
                     }
 
                     
@@ -531,28 +1089,486 @@ impl Expr {
 }
 
 
+//a label can't be reused by a loop inside the loop that already has it
+fn enter_loop(ss: &mut ScopeStack, label: Option<Token>) -> Result<(), SyntaxErr> {
+    if let Some(l) = &label {
+        if ss.inside_breakable(Some(l.data())) {
+            return Err(SyntaxErr::DupLabel(l.clone()))
+        }
+    }
+
+    ss.enter_breakable(label.map(|l| l.data()));
+    Ok(())
+}
+
+//both sides of a binary operator have one type, an untyped literal takes the type of the other side
+fn operand_type(e: &BinaryExpr, left_type: VarType, right_type: VarType) -> Result<VarType, SyntaxErr> {
+    if left_type == right_type {
+        return Ok(left_type)
+    }
+
+    if untyped_literal(&e.right).is_some_and(|v| left_type.fits(v)) {
+        return Ok(left_type)
+    }
+
+    if untyped_literal(&e.left).is_some_and(|v| right_type.fits(v)) {
+        return Ok(right_type)
+    }
+
+    Err(SyntaxErr::WrongType(left_type, right_type))
+}
+
+//a string literal is a [u8; N] but can also be used as a @u8 into read-only data
+fn assignable(expected: &VarType, value: &Expr, value_type: &VarType) -> bool {
+    if expected == value_type {
+        return true
+    }
+
+    if let Expr::Primary(p) = value {
+        if let PrimaryExpr::Literal(Token {ttype: TokenType::Str(_), ..}) = **p {
+            return *expected == VarType::Pointer(Box::new(VarType::U8))
+        }
+    }
+
+    if let Some(v) = untyped_literal(value) {
+        return expected.fits(v)
+    }
+
+    //array literals are checked element by element against the expected element type
+    if let (VarType::Array(t, n), VarType::Array(value_t, value_n)) = (expected, value_type) {
+        if let Expr::Primary(p) = value {
+            match &**p {
+                PrimaryExpr::ArrayLit(elements) => {
+                    return n == value_n && elements.iter().all(|e| assignable(t, e, value_t))
+                }
+                PrimaryExpr::ArrayRepeat(e, _) => return n == value_n && assignable(t, e, value_t),
+                PrimaryExpr::Grouping(g) => return assignable(expected, g, value_type),
+                _ => {}
+            }
+        }
+    }
+
+    false
+}
+
+//integers and pointers convert freely, enums only to and from their own integer type
+//and no casting to or from structs and arrays
+fn castable(from: &VarType, to: &VarType, value: &Expr) -> bool {
+    if assignable(to, value, from) {
+        return true
+    }
+
+    let scalar = |t: &VarType| t.is_integer() || matches!(t, VarType::Pointer(_));
+
+    match (from, to) {
+        (_, VarType::UserEnum(e)) if untyped_literal(value).is_some() => assignable(&e.base, value, from),
+        (VarType::UserEnum(e), t) | (t, VarType::UserEnum(e)) => *t == *e.base,
+        (f, t) => scalar(f) && scalar(t)
+    }
+}
+
+//calls to a function by name, unless a variable of the same name shadows it
+fn direct_callee(callee: &Expr, ss: &ScopeStack) -> Option<FnDeclr> {
+    if let Expr::Primary(p) = callee {
+        if let PrimaryExpr::Id(name) = &**p {
+            if ss.get_var_t(name.data()).is_none() {
+                return ss.get_fn(name.data())
+            }
+        }
+    }
+
+    None
+}
+
+fn check_indirect_call(call: &FnCall, ss: &mut ScopeStack) -> Result<VarType, SyntaxErr> {
+    let (params, ret_type) = match call.callee.check_syntax(ss)? {
+        VarType::Func(params, ret_type) => (params, ret_type),
+        t => return Err(SyntaxErr::NotCallable(call.callee.get_token(), t))
+    };
+
+    if call.args.len() != params.len() {
+        return Err(SyntaxErr::WrongArgN(call.callee.get_token()))
+    }
+
+    for (arg, expected_type) in call.args.iter().zip(params) {
+        let calling_type = arg.check_syntax(ss)?;
+
+        if !assignable(&expected_type, arg, &calling_type) {
+            return Err(SyntaxErr::WrongType(expected_type, calling_type))
+        }
+    }
+
+    Ok(*ret_type)
+}
+
+fn fn_type(f: &FnDeclr, ss: &ScopeStack) -> Result<VarType, SyntaxErr> {
+    let mut params: Vec<VarType> = vec![];
+    for param in f.params.get_param_vec() {
+        match VarType::from(param.1.clone(), &ss.defined_types) {
+            Ok(t) => params.push(t),
+            Err(e) => return Err(SyntaxErr::UnknownType(param.1.get_token(), e))
+        }
+    }
+
+    match VarType::from(f.ret_type.clone(), &ss.defined_types) {
+        Ok(t) => Ok(VarType::Func(params, Box::new(t))),
+        Err(e) => Err(SyntaxErr::UnknownType(f.ret_type.get_token(), e))
+    }
+}
+
+//consts, functions and statics that aren't mut can't be written to
+fn check_writable(place: &Expr, ss: &mut ScopeStack) -> Result<(), SyntaxErr> {
+    if let Some(id) = assigned_var(place, ss)? {
+        if ss.get_const(id.data()).is_some() {
+            return Err(SyntaxErr::AssignToConst(id))
+        }
+
+        //a function's name is its address, which is a value and not a place
+        if ss.get_var_t(id.data()).is_none() && ss.get_fn(id.data()).is_some() {
+            return Err(SyntaxErr::AssignToFn(id))
+        }
+
+        if ss.get_static(id.data()) == Some(false) {
+            return Err(SyntaxErr::AssignToImmutable(id))
+        }
+    }
+
+    Ok(())
+}
+
+//the variable a place expression writes into, None when it writes through a pointer
+fn assigned_var(place: &Expr, ss: &mut ScopeStack) -> Result<Option<Token>, SyntaxErr> {
+    if let Expr::Primary(p) = place {
+        match &**p {
+            PrimaryExpr::Grouping(g) => return assigned_var(g, ss),
+            PrimaryExpr::Id(id) => return Ok(Some(id.clone())),
+            PrimaryExpr::StructField(inner, _) | PrimaryExpr::ArrayAccess(inner, _) => {
+                if matches!(inner.check_syntax(ss)?, VarType::Pointer(_)) {
+                    return Ok(None)
+                }
+                return assigned_var(inner, ss)
+            }
+            _ => {}
+        }
+    }
+
+    Ok(None)
+}
+
+//static initialisers are constants, string literals, addresses of other statics or functions
+//and struct or array literals made of those
+fn static_init(value: &Expr, ss: &ScopeStack) -> bool {
+    if eval_const(value, &ss.defined_types).is_ok() {
+        return true
+    }
+
+    match value {
+        Expr::Ref(r) if r.operator.data() == "&" => match &r.right {
+            Expr::Primary(p) => match &**p {
+                PrimaryExpr::Id(id) => ss.get_static(id.data()).is_some(),
+                _ => false
+            },
+            _ => false
+        },
+        Expr::Primary(p) => match &**p {
+            PrimaryExpr::Grouping(g) => static_init(g, ss),
+            PrimaryExpr::Literal(Token {ttype: TokenType::Str(_), ..}) => true,
+            PrimaryExpr::Id(id) => ss.get_var_t(id.data()).is_none() && ss.get_fn(id.data()).is_some(),
+            PrimaryExpr::StructLit(_, fields) => fields.iter().all(|f| static_init(&f.1, ss)),
+            PrimaryExpr::ArrayLit(elements) => elements.iter().all(|e| static_init(e, ss)),
+            PrimaryExpr::ArrayRepeat(e, _) => static_init(e, ss),
+            _ => false
+        },
+        _ => false
+    }
+}
+
+//lays out an initialiser that static_init accepted, field by field and element by element
+fn static_data(t: &VarType, value: &Expr, ss: &ScopeStack, items: &mut Vec<DataItem>) {
+    if let Ok(v) = eval_const(value, &ss.defined_types) {
+        items.push(DataItem::Value(t.size().expect("statics have a size"), v.value));
+        return
+    }
+
+    match value {
+        Expr::Ref(r) => static_data(t, &r.right, ss, items),
+        Expr::Primary(p) => match (&**p, t) {
+            (PrimaryExpr::Grouping(g), _) => static_data(t, g, ss, items),
+
+            //a string is stored in place as an array, or in rodata when pointed to
+            (PrimaryExpr::Literal(Token {ttype: TokenType::Str(bytes), ..}), VarType::Array(_, _)) => {
+                items.extend(bytes.iter().map(|b| DataItem::Value(1, (*b).into())));
+            }
+            (PrimaryExpr::Literal(Token {ttype: TokenType::Str(bytes), ..}), _) => items.push(DataItem::Str(bytes.clone())),
+
+            (PrimaryExpr::Id(id), _) => items.push(DataItem::Label(id.data())),
+
+            (PrimaryExpr::StructLit(_, fields), VarType::UserStruct(s)) => {
+                for (name, field_type) in &s.fields {
+                    let field = fields.iter().find(|f| f.0.data() == *name).expect("struct literals should have been checked");
+                    static_data(&field_type.unwrap(), &field.1, ss, items);
+                }
+            }
+
+            (PrimaryExpr::ArrayLit(elements), VarType::Array(element_type, _)) => {
+                for e in elements {
+                    static_data(element_type, e, ss, items);
+                }
+            }
+
+            (PrimaryExpr::ArrayRepeat(e, _), VarType::Array(element_type, n)) => {
+                for _ in 0..*n {
+                    static_data(element_type, e, ss, items);
+                }
+            }
+
+            _ => panic!("static initialisers should have been checked")
+        },
+        _ => panic!("static initialisers should have been checked")
+    }
+}
+
+fn smallest_int_type(values: impl Iterator<Item = i128> + Clone) -> Option<VarType> {
+    let candidates = if values.clone().any(|v| v < 0) {
+        [VarType::I8, VarType::I16, VarType::I32, VarType::I64]
+    } else {
+        [VarType::U8, VarType::U16, VarType::U32, VarType::U64]
+    };
+
+    candidates.into_iter().find(|t| values.clone().all(|v| t.fits(v)))
+}
+
+//an integer literal without a suffix (optionally negated) takes on whatever integer type it is used as
+fn untyped_literal(value: &Expr) -> Option<i128> {
+    match value {
+        Expr::Primary(p) => match &**p {
+            PrimaryExpr::Literal(l @ Token {ttype: TokenType::Lit(_), ..}) => {
+                let literal = IntLiteral::parse(&l.data())?;
+                if literal.suffix.is_some() {
+                    return None
+                }
+                Some(literal.value)
+            }
+            PrimaryExpr::Grouping(g) => untyped_literal(g),
+            _ => None
+        },
+        Expr::Unary(u) if u.operator.data() == "-" => untyped_literal(&u.right).map(|v| -v),
+        _ => None
+    }
+}
+
+//-128i8 is lexed as a minus and 128i8, read together they are one literal
+fn negated_literal(u: &UnaryExpr) -> Option<IntLiteral> {
+    if u.operator.data() != "-" {
+        return None
+    }
+
+    match &u.right {
+        Expr::Primary(p) => match &**p {
+            PrimaryExpr::Literal(l @ Token {ttype: TokenType::Lit(_), ..}) => IntLiteral::parse(&format!("-{}", l.data())),
+            _ => None
+        },
+        _ => None
+    }
+}
+
 /*
 Undeclared(VARIABLE_USED),
 WrongType(Should, Is)
 */
 
+//the fields are only read through Debug when main prints the error, which rustc doesn't count
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum SyntaxErr {
     Undeclared(Token),
     WrongType(VarType, VarType),
     UnknownType(Token, &'static str),
+    TypeTooLarge(Token),
     LiteralErr(Token),
     NotDerefAble(Token),
+    NotAddressable(Token),
     WrongArgN(Token),
     DupParamNames(Token),
     ReturnOutsideFunc(Token),
     BreakOutsideLoop(Token),
+    ContinueOutsideLoop(Token),
+    UnknownLabel(Token),
+    DupLabel(Token),
+    InvalidStep(Token),
     ReservedID(Token),
     AlreadyDefined(Token),
     UnknownVariant(Token),
+    NotAnEnum(Token, VarType),
+    UnreachableArm(Token),
+    NonExhaustive(Token, Vec<String>), //(match, missing variants)
     UnknownField(Token),
+    DupField(Token),
+    MissingField(Token, String), //(struct literal, field name)
     NotAStruct(Token),
     RecursiveStruct(Token),
     NotAnArray(Token),
+    InvalidOperand(Token, VarType), //(operator, operand type)
+    InvalidCast(Token, Box<(VarType, VarType)>), //(target type, (from, to))
+    DupDiscriminant(Token),
+    UnknownDiscriminant(Token, i128), //(value cast to the enum, value)
+    DiscriminantOutOfRange(Token, VarType), //(variant, enum type)
+    NotAnInteger(Token, VarType),
+    NotConstant(Token),
+    ConstOverflow(Token),
+    DivideByZero(Token),
+    StaticInFunction(Token),
+    AssignToImmutable(Token),
+    AssignToConst(Token),
+    AssignToFn(Token),
+    NotCallable(Token, VarType),
+    DupAsmOperand(Token),
+    DupClobber(Token),
+    InvalidAsmOperand(Token, VarType), //(operand, type that doesn't fit a register)
+    BadAsmTemplate(Token),
+    UnknownAsmOperand(Token, String), //(assembly line, operand name)
+    AliasCycle(Token, Vec<String>), //(alias, the aliases it expands through back to itself)
+    AliasMismatch(Token, Box<(VarType, VarType)>), //(alias, (its expansion, found))
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn check(text: &str) -> Result<CheckedInfo, SyntaxErr> {
+        let tokens = Lexer::new(text.to_string(), 0).lex();
+        let mut p = Parser::new(tokens);
+        let ast = p.parse();
+
+        assert!(p.errors.is_empty(), "{} did not parse", text);
+        check_ast_syntax(ast)
+    }
+
+    #[test]
+    fn enum_casts() {
+        let color = "enum Color: u16 {Red = 1, Green = 4}\n";
+
+        assert!(check(&format!("{}fn f(x: u16) -> Color {{ return x as Color }}", color)).is_ok());
+        assert!(check(&format!("{}fn f() -> u16 {{ return Color::Green as u16 }}", color)).is_ok());
+        assert!(check(&format!("{}fn f() -> Color {{ return 4 as Color }}", color)).is_ok());
+        assert!(matches!(check(&format!("{}fn f() -> Color {{ return 2 as Color }}", color)), Err(SyntaxErr::UnknownDiscriminant(_, 2))));
+        assert!(matches!(check(&format!("{}fn f(x: u8) -> Color {{ return x as Color }}", color)), Err(SyntaxErr::InvalidCast(..))));
+    }
+
+    #[test]
+    fn aliases() {
+        //the mismatch names both the alias and what it stands for
+        match check("type Id = u8;\nfn main() -> void {\n let b: Id = 300u16;\n}") {
+            Err(SyntaxErr::AliasMismatch(alias, types)) => {
+                assert_eq!(alias.data(), "Id");
+                assert_eq!(*types, (VarType::U8, VarType::U16));
+            }
+            other => panic!("{:?}", other)
+        }
+
+        assert!(check("type N = S;\nstruct S {a: u8}\nstatic n: N;").is_ok());
+        assert!(check("type N = Color;\nenum Color {Red}\nstatic c: N = Color::Red;").is_ok());
+        assert!(check("struct S {next: @Node, v: u8}\ntype Node = S;\nstatic s: S;").is_ok());
+        assert!(matches!(check("struct S {n: N}\ntype N = S;"), Err(SyntaxErr::RecursiveStruct(_))));
+        assert!(matches!(check("type A = B;\ntype B = @A;"), Err(SyntaxErr::AliasCycle(..))));
+    }
+
+    #[test]
+    fn consts_are_not_places() {
+        let n = "const N: u8 = 3;\n";
+
+        assert!(check(&format!("{}fn main() -> void {{\n let a: u8 = N + 1;\n}}", n)).is_ok());
+        assert!(matches!(check(&format!("{}fn main() -> void {{\n N = 4;\n}}", n)), Err(SyntaxErr::AssignToConst(_))));
+        assert!(matches!(check(&format!("{}fn main() -> void {{\n let p: @u8 = &N;\n}}", n)), Err(SyntaxErr::NotAddressable(_))));
+        assert!(matches!(check(&format!("{}fn main() -> void {{\n asm {{ \"in {{v}}\"; out v = N; }}\n}}", n)), Err(SyntaxErr::AssignToConst(_))));
+    }
+
+    #[test]
+    fn functions_are_not_places() {
+        let a = "fn a() -> void {\n}\n";
+
+        assert!(check(&format!("{}fn main() -> void {{\n let f: fn() -> void = a;\n f();\n}}", a)).is_ok());
+        assert!(matches!(check(&format!("{}fn main() -> void {{\n a = main;\n}}", a)), Err(SyntaxErr::AssignToFn(_))));
+        assert!(matches!(check(&format!("{}fn main() -> void {{\n let p: @fn() -> void = &a;\n}}", a)), Err(SyntaxErr::NotAddressable(_))));
+    }
+
+    #[test]
+    fn static_writes() {
+        assert!(check("static mut v: u8;\nfn main() -> void {\n v = 1;\n}").is_ok());
+        assert!(matches!(check("static v: u8 = 1;\nfn main() -> void {\n v = 2;\n}"), Err(SyntaxErr::AssignToImmutable(_))));
+    }
+
+    #[test]
+    fn asm_operands() {
+        let asm = |body: &str| check(&format!("static k: u8 = 1;\nfn main() -> void {{\n let p: u8 = 1;\n let s: u16 = 0;\n asm {{ {} }}\n}}", body));
+
+        assert!(asm("\"out {port}, {value}\"; in port = p; in value = 2; out status = s; clobber a, flags;").is_ok());
+        assert!(matches!(asm("\"nop\"; clobber a, a;"), Err(SyntaxErr::DupClobber(_))));
+        assert!(matches!(asm("\"nop\"; in x = p; in x = p;"), Err(SyntaxErr::DupAsmOperand(_))));
+        assert!(matches!(asm("\"mov {y}, 1\"; in x = p;"), Err(SyntaxErr::UnknownAsmOperand(..))));
+        assert!(matches!(asm("\"nop\"; out x = k;"), Err(SyntaxErr::AssignToImmutable(_))));
+    }
+
+    #[test]
+    fn for_ranges() {
+        let f = |range: &str| check(&format!("fn main() -> void {{\n for i: u8 in {} {{\n let x: u8 = i;\n }}\n}}", range));
+
+        assert!(f("0..10").is_ok());
+        assert!(f("0..=255").is_ok());
+        assert!(f("10..0 step 3").is_ok());
+        assert!(matches!(f("0..=300"), Err(SyntaxErr::WrongType(..))));
+        assert!(matches!(f("0..10 step 0"), Err(SyntaxErr::InvalidStep(_))));
+        assert!(matches!(f("0..10 step 300"), Err(SyntaxErr::WrongType(..))));
+
+        //the loop variable is gone after the loop
+        assert!(matches!(check("fn main() -> void {\n for i: u8 in 0..3 {}\n let x: u8 = i;\n}"), Err(SyntaxErr::Undeclared(_))));
+    }
+
+    #[test]
+    fn loop_labels() {
+        assert!(check("fn main() -> void {\n 'outer: loop {\n  while 1 == 1 {\n   continue 'outer;\n  }\n  break;\n }\n}").is_ok());
+        assert!(check("fn main() -> void {\n for i: u8 in 0..3 {\n  if i == 1 {\n   continue;\n  }\n }\n}").is_ok());
+        assert!(matches!(check("fn main() -> void {\n loop {\n  break 'outer;\n }\n}"), Err(SyntaxErr::UnknownLabel(_))));
+        assert!(matches!(check("fn main() -> void {\n 'a: loop {\n  'a: loop {}\n }\n}"), Err(SyntaxErr::DupLabel(_))));
+        assert!(matches!(check("fn main() -> void {\n break;\n}"), Err(SyntaxErr::BreakOutsideLoop(_))));
+        assert!(matches!(check("fn main() -> void {\n continue;\n}"), Err(SyntaxErr::ContinueOutsideLoop(_))));
+
+        //labels do not reach into nested functions
+        assert!(matches!(check("fn main() -> void {\n 'a: loop {\n  fn f() -> void {\n   loop {\n    break 'a;\n   }\n  }\n }\n}"), Err(SyntaxErr::UnknownLabel(_))));
+    }
+
+    #[test]
+    fn match_arms() {
+        let m = |arms: &str| check(&format!("enum Color {{Red, Green, Blue}}\nfn f(c: Color) -> void {{\n match c {{\n {}\n }}\n}}", arms));
+
+        assert!(m("Color::Red => {}\n Color::Green | Color::Blue => {}").is_ok());
+        assert!(m("Color::Red => {}\n _ => {}").is_ok());
+        assert!(matches!(m("Color::Red => {}"), Err(SyntaxErr::NonExhaustive(_, missing)) if missing == ["Green", "Blue"]));
+        assert!(matches!(m("Color::Red => {}\n Color::Red | Color::Green => {}\n _ => {}"), Err(SyntaxErr::UnreachableArm(_))));
+        assert!(matches!(m("Color::Red | Color::Green | Color::Blue => {}\n _ => {}"), Err(SyntaxErr::UnreachableArm(_))));
+        assert!(matches!(m("_ => {}\n Color::Red => {}"), Err(SyntaxErr::UnreachableArm(_))));
+        assert!(matches!(m("Color::Pink => {}\n _ => {}"), Err(SyntaxErr::UnknownVariant(_))));
+    }
+
+    #[test]
+    fn const_eval() {
+        assert!(check("const N: u16 = 2 * 4 + (1 << 3);\nstatic buf: [u8; N];\nenum E: u8 {A = N as u8, B}\nfn main() -> void {\n const L: u8 = 3;\n let a: [u8; L * 2] = [0; L * 2];\n}").is_ok());
+        assert!(matches!(check("const N: u8 = 200 + 100;"), Err(SyntaxErr::ConstOverflow(_))));
+        assert!(matches!(check("const N: u16 = 1 / (2 - 2);"), Err(SyntaxErr::DivideByZero(_))));
+        assert!(matches!(check("static s: u8 = 1;\nconst N: u8 = s;"), Err(SyntaxErr::NotConstant(_))));
+        assert!(matches!(check("const N: u8 = 2;\nfn main() -> void {\n let a: [u8; N] = [0; 3];\n}"), Err(SyntaxErr::WrongType(..))));
+    }
+
+    #[test]
+    fn extern_calls() {
+        let putc = "extern fn putc(c: u8) -> void;\n";
+
+        assert!(check(&format!("{}export fn tick(n: u16) -> u16 {{\n putc(65);\n return n + 1\n}}", putc)).is_ok());
+        assert!(matches!(check(&format!("{}fn main() -> void {{\n putc(65, 66);\n}}", putc)), Err(SyntaxErr::WrongArgN(_))));
+        assert!(matches!(check(&format!("{}fn main() -> void {{\n putc(300u16);\n}}", putc)), Err(SyntaxErr::WrongType(..))));
+        assert!(matches!(check(&format!("{}fn putc(c: u8) -> void {{}}", putc)), Err(SyntaxErr::AlreadyDefined(_))));
+    }
+}
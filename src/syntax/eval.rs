@@ -0,0 +1,273 @@
+use super::*;
+
+//an integer known at compile time, var_type is None for unsuffixed literals
+//and anything computed only from them, those take on the type they are used as
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstValue {
+    pub value: i128,
+    pub var_type: Option<VarType>,
+}
+
+impl ConstValue {
+    fn typed(value: i128, var_type: VarType) -> Self {
+        ConstValue { value, var_type: Some(var_type) }
+    }
+
+    //whether the value can be stored as t
+    pub fn fits(&self, t: &VarType) -> bool {
+        match &self.var_type {
+            Some(own) => own == t,
+            None => t.fits(self.value)
+        }
+    }
+}
+
+//array sizes can be any integer constant that fits a u16
+pub fn eval_array_len(e: &Expr, defined_types: &Vec<UserType>) -> Result<u16, &'static str> {
+    let len = match eval_const(e, defined_types) {
+        Ok(len) => len,
+        Err(_) => return Err("Array Size is not a Constant")
+    };
+
+    match &len.var_type {
+        Some(t) if !t.is_integer() => Err("Array Size is not an Integer"),
+        _ => u16::try_from(len.value).map_err(|_| "Array Size out of Range")
+    }
+}
+
+pub fn eval_const(e: &Expr, defined_types: &Vec<UserType>) -> Result<ConstValue, SyntaxErr> {
+    match e {
+        Expr::Primary(p) => match &**p {
+            PrimaryExpr::Grouping(g) => eval_const(g, defined_types),
+
+            PrimaryExpr::Literal(l) => match &l.ttype {
+                TokenType::Char(c) => Ok(ConstValue::typed(*c as i128, VarType::U8)),
+                TokenType::Lit(text) => {
+                    let literal = match IntLiteral::parse(text) {
+                        Some(literal) => literal,
+                        None => return Err(SyntaxErr::LiteralErr(l.clone()))
+                    };
+
+                    if literal.var_type().is_none() {
+                        return Err(SyntaxErr::LiteralErr(l.clone()))
+                    }
+
+                    Ok(ConstValue {
+                        value: literal.value,
+                        var_type: literal.suffix })
+                }
+                _ => Err(SyntaxErr::NotConstant(l.clone()))
+            },
+
+            PrimaryExpr::Id(id) => {
+                for user_type in defined_types.iter().rev() {
+                    if let UserType::Const(c) = user_type {
+                        if c.name == id.data() {
+                            return Ok(ConstValue::typed(c.value, c.var_type.clone()))
+                        }
+                    }
+                }
+
+                Err(SyntaxErr::NotConstant(id.clone()))
+            }
+
+            PrimaryExpr::EnumVariant(e_name, variant) => {
+                for user_type in defined_types.iter().rev() {
+                    if let UserType::UserEnum(user_enum) = user_type {
+                        if user_enum.name == e_name.data() {
+                            return match user_enum.variants.iter().find(|v| v.0 == variant.data()) {
+                                Some(v) => Ok(ConstValue::typed(v.1, VarType::UserEnum(user_enum.clone()))),
+                                None => Err(SyntaxErr::UnknownVariant(variant.clone()))
+                            }
+                        }
+                    }
+                }
+
+                Err(SyntaxErr::UnknownType(e_name.clone(), "Undefined Enum"))
+            }
+
+            PrimaryExpr::SizeOf(_, SizeOfArg::Type(t)) => size_of(&resolve(t, defined_types)?, t.get_token()),
+
+            //without variables in scope a value only has a known type if it is itself a constant
+            PrimaryExpr::SizeOf(_, SizeOfArg::Expr(value)) => {
+                let value_type = match eval_const(value, defined_types) {
+                    Ok(ConstValue {var_type: Some(t), ..}) => t,
+                    Ok(_) => return Err(SyntaxErr::NotConstant(value.get_token())),
+                    Err(err) => match value.as_type_name().map(|t| resolve(&t, defined_types)) {
+                        Some(Ok(t)) => t,
+                        _ => return Err(err)
+                    }
+                };
+
+                size_of(&value_type, value.get_token())
+            }
+
+            PrimaryExpr::AlignOf(_, t) => Ok(ConstValue::typed(resolve(t, defined_types)?.align().into(), VarType::U16)),
+
+            PrimaryExpr::OffsetOf(_, t, field) => {
+                let user_struct = match resolve(t, defined_types)? {
+                    VarType::UserStruct(s) => s,
+                    _ => return Err(SyntaxErr::NotAStruct(t.get_token()))
+                };
+
+                //every offset fits once the whole struct does
+                size_of(&VarType::UserStruct(user_struct.clone()), t.get_token())?;
+
+                match user_struct.field_offset(field.data()) {
+                    Some(offset) => Ok(ConstValue::typed(offset.into(), VarType::U16)),
+                    None => Err(SyntaxErr::UnknownField(field.clone()))
+                }
+            }
+
+            _ => Err(SyntaxErr::NotConstant(e.get_token()))
+        },
+
+        Expr::Unary(u) => {
+            if let Some(literal) = negated_literal(u) {
+                if literal.var_type().is_none() {
+                    return Err(SyntaxErr::LiteralErr(u.right.get_token()))
+                }
+
+                return Ok(ConstValue {
+                    value: literal.value,
+                    var_type: literal.suffix })
+            }
+
+            let right = integer_operand(eval_const(&u.right, defined_types)?, &u.operator)?;
+
+            if let Some(t) = &right.var_type {
+                if u.operator.data() == "-" && !t.fits(-1) {
+                    return Err(SyntaxErr::InvalidOperand(u.operator.clone(), t.clone()))
+                }
+            }
+
+            let value = match u.operator.data().as_str() {
+                "-" => right.value.checked_neg(),
+                "~" => Some(!right.value),
+                _ => return Ok(ConstValue::typed((right.value == 0).into(), VarType::U8))
+            };
+
+            //~ on an unsigned type keeps only its own bits
+            let value = match (&right.var_type, u.operator.data().as_str()) {
+                (Some(t), "~") => value.map(|v| wrap(v, t)),
+                _ => value
+            };
+
+            checked(value, right.var_type, &u.operator)
+        }
+
+        //casts between integers truncate like they do at runtime
+        Expr::Cast(c) => {
+            let value = eval_const(&c.value, defined_types)?;
+
+            let to_type = match VarType::from(c.to_type.clone(), defined_types) {
+                Ok(t) => t,
+                Err(err) => return Err(SyntaxErr::UnknownType(c.to_type.get_token(), err))
+            };
+
+            match &to_type {
+                t if t.is_integer() => Ok(ConstValue::typed(wrap(value.value, t), to_type)),
+                VarType::UserEnum(user_enum) if user_enum.variants.iter().any(|v| v.1 == value.value) => {
+                    Ok(ConstValue::typed(value.value, to_type))
+                }
+                _ => Err(SyntaxErr::NotConstant(c.to_type.get_token()))
+            }
+        }
+
+        Expr::LogicOr(b) | Expr::LogicAnd(b) |
+        Expr::Equality(b) | Expr::Comparison(b) |
+        Expr::BitOr(b) | Expr::BitXor(b) | Expr::BitAnd(b) |
+        Expr::Shift(b) | Expr::Term(b) | Expr::Factor(b) => {
+            let left = eval_const(&b.left, defined_types)?;
+            let right = eval_const(&b.right, defined_types)?;
+            let op = b.operator.data();
+
+            //== and != also work on enums
+            if matches!(e, Expr::Equality(_)) && left.var_type == right.var_type {
+                return Ok(ConstValue::typed(((left.value == right.value) == (op == "==")).into(), VarType::U8))
+            }
+
+            let left = integer_operand(left, &b.operator)?;
+            let right = integer_operand(right, &b.operator)?;
+
+            let var_type = match (left.var_type, right.var_type) {
+                (Some(l), Some(r)) if l != r => return Err(SyntaxErr::WrongType(l, r)),
+                (l, r) => l.or(r)
+            };
+
+            let (l, r) = (left.value, right.value);
+            let truth = |b: bool| Ok(ConstValue::typed(b.into(), VarType::U8));
+
+            let value = match op.as_str() {
+                "||" => return truth(l != 0 || r != 0),
+                "&&" => return truth(l != 0 && r != 0),
+                "==" => return truth(l == r),
+                "!=" => return truth(l != r),
+                "<" => return truth(l < r),
+                ">" => return truth(l > r),
+                "<=" => return truth(l <= r),
+                ">=" => return truth(l >= r),
+                "|" => Some(l | r),
+                "^" => Some(l ^ r),
+                "&" => Some(l & r),
+                "+" => l.checked_add(r),
+                "-" => l.checked_sub(r),
+                "*" => l.checked_mul(r),
+                "/" | "%" if r == 0 => return Err(SyntaxErr::DivideByZero(b.operator.clone())),
+                "/" => l.checked_div(r),
+                "%" => l.checked_rem(r),
+                _ => {
+                    //shifting by the width of the type or more is an overflow
+                    let bits = var_type.as_ref().map_or(127, |t| t.size().expect("integers have a size") as i128 * 8);
+                    if r < 0 || r >= bits {
+                        return Err(SyntaxErr::ConstOverflow(b.operator.clone()))
+                    }
+
+                    if op == "<<" {l.checked_mul(1 << r)} else {Some(l >> r)}
+                }
+            };
+
+            checked(value, var_type, &b.operator)
+        }
+
+        _ => Err(SyntaxErr::NotConstant(e.get_token()))
+    }
+}
+
+fn resolve(t: &DeclrType, defined_types: &Vec<UserType>) -> Result<VarType, SyntaxErr> {
+    VarType::from(t.clone(), defined_types).map_err(|err| SyntaxErr::UnknownType(t.get_token(), err))
+}
+
+fn integer_operand(v: ConstValue, operator: &Token) -> Result<ConstValue, SyntaxErr> {
+    match &v.var_type {
+        Some(t) if !t.is_integer() => Err(SyntaxErr::InvalidOperand(operator.clone(), t.clone())),
+        _ => Ok(v)
+    }
+}
+
+fn size_of(t: &VarType, token: Token) -> Result<ConstValue, SyntaxErr> {
+    match t.size() {
+        Some(size) => Ok(ConstValue::typed(size.into(), VarType::U16)),
+        None => Err(SyntaxErr::TypeTooLarge(token))
+    }
+}
+
+//the result has to fit its type, untyped results only have to fit an i128
+fn checked(value: Option<i128>, var_type: Option<VarType>, operator: &Token) -> Result<ConstValue, SyntaxErr> {
+    match value {
+        Some(value) if var_type.as_ref().is_none_or(|t| t.fits(value)) => Ok(ConstValue { value, var_type }),
+        _ => Err(SyntaxErr::ConstOverflow(operator.clone()))
+    }
+}
+
+//two's complement truncation to an integer type
+fn wrap(value: i128, t: &VarType) -> i128 {
+    let bits = t.size().expect("integers have a size") as u32 * 8;
+    let modulus = 1i128 << bits;
+    let v = value.rem_euclid(modulus);
+
+    match t.int_bounds() {
+        Some((min, _)) if min < 0 && v >= modulus / 2 => v - modulus,
+        _ => v
+    }
+}
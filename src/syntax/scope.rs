@@ -5,7 +5,8 @@ use super::*;
 pub struct ScopeStack {
     pub stack: Vec<ScopeStackOp>,
     pub defined_types: Vec<UserType>,
-    pub used_ids: Vec<String>
+    pub used_ids: Vec<String>,
+    pub info: CheckedInfo,
 }
 
 impl ScopeStack {
@@ -47,6 +48,26 @@ impl ScopeStack {
         self.used_ids.push(name.clone());
     }
 
+    pub fn static_declr(&mut self, name: String, t: VarType, mutable: bool) {
+        self.stack.push(ScopeStackOp::Static(VarData {
+            name: name.clone(),
+            var_type: t
+        }, mutable));
+        self.used_ids.push(name.clone());
+    }
+
+    //whether the static is mutable, None if the name isn't a static or a local shadows it
+    pub fn get_static(&self, target_name: String) -> Option<bool> {
+        for element in self.stack.iter().rev() {
+            match element {
+                ScopeStackOp::Variable(var) if var.name == target_name => return None,
+                ScopeStackOp::Static(var, mutable) if var.name == target_name => return Some(*mutable),
+                _ => {}
+            }
+        }
+        None
+    }
+
     pub fn get_user_enum(&self, name: String) -> Option<UserEnumDef> {
         for user_type in self.defined_types.iter().rev() {
             if let UserType::UserEnum(e) = user_type {
@@ -59,22 +80,82 @@ impl ScopeStack {
         None
     }
 
-    pub fn enter_breakable(&mut self) {
-        self.stack.push(ScopeStackOp::EnterBreakable);
+    pub fn get_user_struct(&self, name: String) -> Option<UserStructDef> {
+        for user_type in self.defined_types.iter().rev() {
+            if let UserType::UserStruct(s) = user_type {
+                if s.name == name {
+                    return Some(s.clone())
+                }
+            }
+        }
+
+        None
+    }
+
+    //a variable declared after a const of the same name shadows it
+    pub fn get_const(&self, name: String) -> Option<ConstDef> {
+        for element in self.stack.iter().rev() {
+            match element {
+                ScopeStackOp::Variable(var) | ScopeStackOp::Static(var, _) if var.name == name => return None,
+                ScopeStackOp::UserType(UserType::Const(c)) if c.name == name => return Some(c.clone()),
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    pub fn get_alias(&self, name: String) -> Option<AliasDef> {
+        for user_type in self.defined_types.iter().rev() {
+            if let UserType::Alias(a) = user_type {
+                if a.name == name {
+                    return Some(a.clone())
+                }
+            }
+        }
+
+        None
+    }
+
+    //names the alias as well when the expected type was written as one
+    pub fn type_mismatch(&self, declared: &DeclrType, expected: VarType, found: VarType) -> SyntaxErr {
+        match declared {
+            DeclrType::BasicType(t) if self.get_alias(t.data()).is_some() => SyntaxErr::AliasMismatch(t.clone(), Box::new((expected, found))),
+            _ => SyntaxErr::WrongType(expected, found)
+        }
+    }
+
+    //whether a name refers to a variable, const or function rather than a type
+    pub fn is_value(&self, name: &Token) -> bool {
+        self.get_var_t(name.data()).is_some() || self.get_const(name.data()).is_some() || self.get_fn(name.data()).is_some()
+    }
+
+    pub fn enter_breakable(&mut self, label: Option<String>) {
+        self.stack.push(ScopeStackOp::EnterBreakable(label));
+    }
+
+    pub fn leave_breakable(&mut self) {
+        while !matches!(self.stack[self.stack.len() - 1], ScopeStackOp::EnterBreakable(_)) {
+            self.stack.pop();
+        }
+        self.stack.pop();
     }
 
-    pub fn inside_breakable(&mut self) -> bool {
+    //without a label any enclosing loop will do, loops outside the current function don't count
+    pub fn inside_breakable(&self, label: Option<String>) -> bool {
         for element in self.stack.iter().rev() {
-            if matches!(element, ScopeStackOp::EnterBreakable) {
-                return true
+            match element {
+                ScopeStackOp::EnterFuncDef => return false,
+                ScopeStackOp::EnterBreakable(l) if label.is_none() || *l == label => return true,
+                _ => {}
             }
         }
-        return false
+        false
     }
 
     pub fn fn_declr(&mut self, f: FnDeclr) {
-        self.stack.push(ScopeStackOp::Func(f.clone()));
-        self.used_ids.push(f.name.data())
+        self.used_ids.push(f.name.data());
+        self.stack.push(ScopeStackOp::Func(Box::new(f)))
     }
 
     pub fn enter_func_def(&mut self) {
@@ -90,7 +171,7 @@ impl ScopeStack {
 
     pub fn get_var_t(&self, target_name: String) -> Option<VarType> {
         for element in self.stack.iter().rev() {
-            if let ScopeStackOp::Variable(var) = element {
+            if let ScopeStackOp::Variable(var) | ScopeStackOp::Static(var, _) = element {
                 if var.name == target_name {
                     return Some(var.var_type.clone())
                 }
@@ -103,7 +184,7 @@ impl ScopeStack {
         for element in self.stack.iter().rev() {
             if let ScopeStackOp::Func(func) = element {
                 if func.name.data() == target_name {
-                    return Some(func.clone())
+                    return Some(*func.clone())
                 }
             } 
         }
@@ -115,7 +196,7 @@ impl ScopeStack {
         for element in self.stack.iter().rev() {
             if next_is_template {
                 if let ScopeStackOp::Func(f) = element {
-                    return Some(f.clone())
+                    return Some(*f.clone())
                 }
                 panic!("this was supposed to work")
             }
@@ -133,8 +214,9 @@ impl ScopeStack {
 pub enum ScopeStackOp {
     EnterScope(Vec<String>),
     EnterFuncDef,
-    EnterBreakable,
+    EnterBreakable(Option<String>), //loop label
     UserType(UserType),
     Variable(VarData),
-    Func(FnDeclr),
+    Static(VarData, bool), //(variable, mutable)
+    Func(Box<FnDeclr>),
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use super::DeclrType;
+use super::eval::eval_array_len;
 
 #[derive(Debug, Clone)]
 pub struct VarData {
@@ -20,33 +21,72 @@ pub enum VarType {
     UserStruct(UserStructDef),
     UserEnum(UserEnumDef),
     Void,
-    Array(Box<VarType>, u16)
+    Array(Box<VarType>, u16),
+    Func(Vec<VarType>, Box<VarType>), //(params, return type)
 }
 
 impl VarType {
-    pub fn size(&self) -> u16 {
+    //None if the type doesn't fit in the 16 bit address space
+    pub fn size(&self) -> Option<u16> {
         match self {
-            Self::U8 => 1,
-            Self::I8 => 1,
-            Self::U16 => 2,
-            Self::I16 => 2,
-            Self::U32 => 4,
-            Self::I32 => 4,
-            Self::U64 => 8,
-            Self::I64 => 8,
-            Self::Pointer(_) => 2,
+            Self::U8 => Some(1),
+            Self::I8 => Some(1),
+            Self::U16 => Some(2),
+            Self::I16 => Some(2),
+            Self::U32 => Some(4),
+            Self::I32 => Some(4),
+            Self::U64 => Some(8),
+            Self::I64 => Some(8),
+            Self::Pointer(_) => Some(2),
+            Self::Func(_, _) => Some(2),
             Self::UserStruct(s) => {
                 let mut sum: u16 = 0;
                 for t in &s.fields {
-                    sum += t.1.unwrap().size();
+                    sum = sum.checked_add(t.1.unwrap().size()?)?;
                 }
-                sum
+                Some(sum)
             }
-            Self::Void => 0,
-            Self::Array(t, s) => t.size() * s,
-            Self::UserEnum(_) => 1,
-            _ => panic!()
+            Self::Void => Some(0),
+            Self::Array(t, s) => t.size()?.checked_mul(*s),
+            Self::UserEnum(e) => e.base.size(),
+        }
+    }
 
+    //the alignment the 16 bit target prefers, structs are packed so their fields
+    //can start anywhere and the struct itself needs no alignment
+    pub fn align(&self) -> u16 {
+        match self {
+            Self::U8 | Self::I8 | Self::Void => 1,
+            Self::UserStruct(_) => 1,
+            Self::Array(t, _) => t.align(),
+            Self::UserEnum(e) => e.base.align(),
+            _ => 2
+        }
+    }
+
+    //inclusive range of values an integer type can hold
+    pub fn int_bounds(&self) -> Option<(i128, i128)> {
+        match self {
+            Self::U8 => Some((0, u8::MAX.into())),
+            Self::I8 => Some((i8::MIN.into(), i8::MAX.into())),
+            Self::U16 => Some((0, u16::MAX.into())),
+            Self::I16 => Some((i16::MIN.into(), i16::MAX.into())),
+            Self::U32 => Some((0, u32::MAX.into())),
+            Self::I32 => Some((i32::MIN.into(), i32::MAX.into())),
+            Self::U64 => Some((0, u64::MAX.into())),
+            Self::I64 => Some((i64::MIN.into(), i64::MAX.into())),
+            _ => None
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.int_bounds().is_some()
+    }
+
+    pub fn fits(&self, value: i128) -> bool {
+        match self.int_bounds() {
+            Some((min, max)) => value >= min && value <= max,
+            None => false
         }
     }
 
@@ -88,61 +128,47 @@ impl VarType {
                                         return Ok(VarType::UserEnum(e.clone()))
                                     }
                                 }
+
+                                UserType::Alias(a) => {
+                                    if a.name == t {
+                                        return Ok(a.var_type.clone())
+                                    }
+                                }
+
+                                UserType::Const(_) => {}
                                 
                             }
                         }
         
-                        return Err("Undefined Type")
+                        Err("Undefined Type")
                     }
                 }
             }
 
             DeclrType::Array(a_type, a_size) => {
                 let temp = VarType::from(*a_type.clone(), defined_types)?;
-                let a_size_int = match a_size.ttype.data().parse::<u16>() {
-                    Ok(t) => t,
-                    Err(_) => return Err("Cannot Parse Array Size"),
-                };
+                let a_size_int = eval_array_len(&a_size, defined_types)?;
                 
-                return Ok(VarType::Array(Box::new(temp), a_size_int))
+                Ok(VarType::Array(Box::new(temp), a_size_int))
             }
 
             DeclrType::Pointer(points_to) => {
                 let temp = VarType::from(*points_to.clone(), defined_types)?;
-                return Ok(VarType::Pointer(Box::new(temp)))
+                Ok(VarType::Pointer(Box::new(temp)))
             }
-        }
 
-        
-    }
+            DeclrType::Func(_, params, ret_type) => {
+                let mut param_types: Vec<VarType> = vec![];
+                for param in params {
+                    param_types.push(VarType::from(param, defined_types)?);
+                }
 
-    pub fn to_string(&self) -> String {
-        match self.clone() {
-            Self::U8 => "u8".to_string(),
-            Self::I8 => "i8".to_string(),
-            Self::U16 => "u16".to_string(),
-            Self::I16 => "i16".to_string(),
-            Self::U32 => "u32".to_string(),
-            Self::I32 => "i32".to_string(),
-            Self::U64 => "u64".to_string(),
-            Self::I64 => "i64".to_string(),
-
-            Self::Pointer(t) => t.to_string(),
-            Self::Array(t, s) => {
-                let mut temp = String::new();
-                temp.push_str("[");
-                temp.push_str(t.to_string().as_str());
-                temp.push_str(";");
-                temp.push_str(format!("{}",s).as_str());
-                temp.push_str("]");
-                temp
+                let ret_type = VarType::from(*ret_type, defined_types)?;
+                Ok(VarType::Func(param_types, Box::new(ret_type)))
             }
-
-            Self::UserEnum(e) => e.name,
-            Self::UserStruct(s) => s.name,
-            Self::Void => "void".to_string()
-
         }
+
+        
     }
 }
 
@@ -150,17 +176,36 @@ impl VarType {
 pub enum UserType {
     UserStruct(UserStructDef),
     UserEnum(UserEnumDef),
+    Const(ConstDef),
+    Alias(AliasDef),
 }
 
 impl UserType {
     pub fn name(&self) -> String {
         match self {
             UserType::UserStruct(s) => s.name.clone(),
-            UserType::UserEnum(e) => e.name.clone() 
+            UserType::UserEnum(e) => e.name.clone(),
+            UserType::Const(c) => c.name.clone(),
+            UserType::Alias(a) => a.name.clone(),
         }
     }
 }
 
+//aliases are resolved when declared, so they are just another name for var_type
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasDef {
+    pub name: String,
+    pub var_type: VarType,
+}
+
+//consts live alongside the user types so that type resolution can evaluate array sizes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstDef {
+    pub name: String,
+    pub var_type: VarType,
+    pub value: i128,
+}
+
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct UserStructDef {
@@ -181,20 +226,6 @@ impl FieldType {
             FieldType::Undefined(s) => panic!("Undefined Field Type {}", s)
         }
     }
-
-    pub fn name_to_string(&self) -> String {
-        match self.clone() {
-            Self::Defined(t) => t.to_string(),
-            Self::Undefined(t) => {
-                if let DeclrType::BasicType(basic) = t {
-                    basic.ttype.data()
-                } else {
-                    //this might need to be changed
-                    "".to_string()
-                }
-            }
-        }
-    }
 }
 
 impl UserStructDef {
@@ -204,24 +235,103 @@ impl UserStructDef {
                 return Some(f.1)
             }
         }
-        return None
+        None
+    }
+
+    //fields are packed, so a field starts right after the ones before it
+    //None if there is no such field or the struct is too large
+    pub fn field_offset(&self, name: String) -> Option<u16> {
+        let mut offset: u16 = 0;
+        for f in &self.fields {
+            if f.0 == name {
+                return Some(offset)
+            }
+            offset = offset.checked_add(f.1.unwrap().size()?)?;
+        }
+        None
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct UserEnumDef {
     pub name: String,
-    pub variants: Vec<String>
+    pub base: Box<VarType>, //the integer type the discriminants are stored as
+    pub variants: Vec<(String, i128)> //(name, discriminant)
 }
 
 
 impl UserEnumDef {
     pub fn check_variant(&self, v_target: String) -> bool {
         for v in self.variants.clone() {
-            if v == v_target {
+            if v.0 == v_target {
                 return true
             }
         }
         false
     }
 }
+
+
+//integer literal as written in the source, e.g. 0xFF00, 0b1010_0101, 255u8
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntLiteral {
+    pub value: i128,
+    pub suffix: Option<VarType>,
+}
+
+impl IntLiteral {
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut text = text.replace('_', "");
+        
+        let mut suffix = None;
+        for (s, t) in [("u8", VarType::U8), ("i8", VarType::I8),
+                       ("u16", VarType::U16), ("i16", VarType::I16),
+                       ("u32", VarType::U32), ("i32", VarType::I32),
+                       ("u64", VarType::U64), ("i64", VarType::I64)] {
+            
+            if text.ends_with(s) {
+                text.truncate(text.len() - s.len());
+                suffix = Some(t);
+                break;
+            }
+        }
+
+        let negative = text.starts_with('-');
+        let digits = text.trim_start_matches('-');
+
+        let (radix, digits) = match digits.get(..2) {
+            Some("0x") => (16, &digits[2..]),
+            Some("0b") => (2, &digits[2..]),
+            Some("0o") => (8, &digits[2..]),
+            _ => (10, digits)
+        };
+
+        if digits.is_empty() {
+            return None
+        }
+
+        let magnitude = i128::from_str_radix(digits, radix).ok()?;
+
+        Some(IntLiteral {
+            value: if negative {-magnitude} else {magnitude},
+            suffix })
+    }
+
+    //the suffix if there is one, otherwise the smallest type the value fits in
+    pub fn var_type(&self) -> Option<VarType> {
+        if let Some(t) = &self.suffix {
+            if t.fits(self.value) {
+                return Some(t.clone())
+            }
+            return None
+        }
+
+        let candidates = if self.value < 0 {
+            [VarType::I8, VarType::I16, VarType::I32, VarType::I64]
+        } else {
+            [VarType::U8, VarType::U16, VarType::U32, VarType::U64]
+        };
+
+        candidates.into_iter().find(|t| t.fits(self.value))
+    }
+}